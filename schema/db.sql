@@ -65,3 +65,360 @@ DROP TRIGGER IF EXISTS "todo_updated_at_" ON "todo_";
 CREATE TRIGGER "todo_updated_at_" BEFORE
 UPDATE
     ON "todo_" FOR EACH ROW EXECUTE FUNCTION updated_at();
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "due_at" BIGINT;
+
+CREATE INDEX IF NOT EXISTS "idx_todo_user_id_due_at_" ON "todo_" ("user_id", "due_at");
+
+DO $$
+BEGIN
+IF NOT EXISTS (
+    SELECT
+        1
+    FROM
+        pg_type
+    WHERE
+        typname = 'priority_'
+) THEN
+CREATE TYPE "priority_" AS ENUM ('none', 'low', 'medium', 'high', 'urgent');
+
+END IF;
+
+END;
+
+$$;
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "priority" "priority_" NOT NULL DEFAULT 'none';
+
+CREATE INDEX IF NOT EXISTS "idx_todo_user_id_priority_" ON "todo_" ("user_id", "priority" DESC, "due_at");
+
+CREATE TABLE IF NOT EXISTS "tag_" (
+    "id" VARCHAR(26) PRIMARY KEY DEFAULT gen_ulid(),
+    "user_id" VARCHAR(26) NOT NULL,
+    "name" VARCHAR(50) NOT NULL,
+    "color" VARCHAR(7),
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    "updated_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    CONSTRAINT "fk_tag_user_id_" FOREIGN KEY ("user_id") REFERENCES "user_" ("id") ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_tag_user_id_name_" ON "tag_" ("user_id", "name");
+
+DROP TRIGGER IF EXISTS "tag_updated_at_" ON "tag_";
+
+CREATE TRIGGER "tag_updated_at_" BEFORE
+UPDATE
+    ON "tag_" FOR EACH ROW EXECUTE FUNCTION updated_at();
+
+CREATE TABLE IF NOT EXISTS "todo_tag_" (
+    "todo_id" VARCHAR(26) NOT NULL,
+    "tag_id" VARCHAR(26) NOT NULL,
+    PRIMARY KEY ("todo_id", "tag_id"),
+    CONSTRAINT "fk_todo_tag_todo_id_" FOREIGN KEY ("todo_id") REFERENCES "todo_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_todo_tag_tag_id_" FOREIGN KEY ("tag_id") REFERENCES "tag_" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_todo_tag_tag_id_" ON "todo_tag_" ("tag_id");
+
+CREATE TABLE IF NOT EXISTS "project_" (
+    "id" VARCHAR(26) PRIMARY KEY DEFAULT gen_ulid(),
+    "user_id" VARCHAR(26) NOT NULL,
+    "name" VARCHAR(100) NOT NULL,
+    "archived" BOOLEAN NOT NULL DEFAULT FALSE,
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    "updated_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    CONSTRAINT "fk_project_user_id_" FOREIGN KEY ("user_id") REFERENCES "user_" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_project_user_id_" ON "project_" ("user_id");
+
+DROP TRIGGER IF EXISTS "project_updated_at_" ON "project_";
+
+CREATE TRIGGER "project_updated_at_" BEFORE
+UPDATE
+    ON "project_" FOR EACH ROW EXECUTE FUNCTION updated_at();
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "project_id" VARCHAR(26) CONSTRAINT "fk_todo_project_id_" REFERENCES "project_" ("id") ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS "idx_todo_project_id_" ON "todo_" ("project_id");
+
+CREATE TABLE IF NOT EXISTS "subtask_" (
+    "id" VARCHAR(26) PRIMARY KEY DEFAULT gen_ulid(),
+    "todo_id" VARCHAR(26) NOT NULL,
+    "title" VARCHAR(255) NOT NULL,
+    "completed" BOOLEAN NOT NULL DEFAULT FALSE,
+    "position" INTEGER NOT NULL DEFAULT 0,
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    "updated_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    CONSTRAINT "fk_subtask_todo_id_" FOREIGN KEY ("todo_id") REFERENCES "todo_" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_subtask_todo_id_position_" ON "subtask_" ("todo_id", "position");
+
+DROP TRIGGER IF EXISTS "subtask_updated_at_" ON "subtask_";
+
+CREATE TRIGGER "subtask_updated_at_" BEFORE
+UPDATE
+    ON "subtask_" FOR EACH ROW EXECUTE FUNCTION updated_at();
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "recurrence" JSONB;
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "search" TSVECTOR GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', "title"), 'A') || setweight(to_tsvector('english', "content"), 'B')
+) STORED;
+
+CREATE INDEX IF NOT EXISTS "idx_todo_search_" ON "todo_" USING GIN ("search");
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "deleted_at" BIGINT;
+
+CREATE INDEX IF NOT EXISTS "idx_todo_deleted_at_" ON "todo_" ("deleted_at") WHERE "deleted_at" IS NOT NULL;
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "archived" BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "position" VARCHAR(255) COLLATE "C";
+
+WITH "ranked_" AS (
+    SELECT "id", row_number() OVER (PARTITION BY "user_id" ORDER BY "id") AS "rank"
+    FROM "todo_"
+    WHERE "position" IS NULL
+)
+UPDATE "todo_" SET "position" = 'c'
+    || substr('0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz', ("ranked_"."rank" / 3844 % 62)::INTEGER + 1, 1)
+    || substr('0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz', ("ranked_"."rank" / 62 % 62)::INTEGER + 1, 1)
+    || substr('0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz', ("ranked_"."rank" % 62)::INTEGER + 1, 1)
+FROM "ranked_"
+WHERE "todo_"."id" = "ranked_"."id";
+
+ALTER TABLE "todo_" ALTER COLUMN "position" SET NOT NULL;
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "version" INTEGER NOT NULL DEFAULT 1;
+
+CREATE
+OR REPLACE FUNCTION bump_version() RETURNS TRIGGER AS
+$$
+BEGIN
+NEW.version = OLD.version + 1;
+
+RETURN NEW;
+
+END;
+
+$$
+LANGUAGE 'plpgsql';
+
+DROP TRIGGER IF EXISTS "todo_version_" ON "todo_";
+
+CREATE TRIGGER "todo_version_" BEFORE
+UPDATE
+    ON "todo_" FOR EACH ROW EXECUTE FUNCTION bump_version();
+
+DO $$
+BEGIN
+IF NOT EXISTS (
+    SELECT
+        1
+    FROM
+        pg_type
+    WHERE
+        typname = 'todo_action_'
+) THEN
+CREATE TYPE "todo_action_" AS ENUM ('create', 'update', 'mark', 'delete', 'restore');
+
+END IF;
+
+END;
+
+$$;
+
+CREATE TABLE IF NOT EXISTS "todo_event_" (
+    "id" VARCHAR(26) PRIMARY KEY DEFAULT gen_ulid(),
+    "todo_id" VARCHAR(26) NOT NULL,
+    "user_id" VARCHAR(26) NOT NULL,
+    "session_id" VARCHAR(26),
+    "action" "todo_action_" NOT NULL,
+    "old_value" JSONB,
+    "new_value" JSONB,
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    CONSTRAINT "fk_todo_event_todo_id_" FOREIGN KEY ("todo_id") REFERENCES "todo_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_todo_event_user_id_" FOREIGN KEY ("user_id") REFERENCES "user_" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_todo_event_todo_id_" ON "todo_event_" ("todo_id", "id");
+
+ALTER TYPE "todo_action_" ADD VALUE IF NOT EXISTS 'undo';
+
+DO $$
+BEGIN
+IF NOT EXISTS (
+    SELECT
+        1
+    FROM
+        pg_type
+    WHERE
+        typname = 'share_permission_'
+) THEN
+CREATE TYPE "share_permission_" AS ENUM ('viewer', 'editor');
+
+END IF;
+
+IF NOT EXISTS (
+    SELECT
+        1
+    FROM
+        pg_type
+    WHERE
+        typname = 'share_status_'
+) THEN
+CREATE TYPE "share_status_" AS ENUM ('pending', 'accepted', 'declined');
+
+END IF;
+
+END;
+
+$$;
+
+CREATE TABLE IF NOT EXISTS "share_" (
+    "id" VARCHAR(26) PRIMARY KEY DEFAULT gen_ulid(),
+    "owner_id" VARCHAR(26) NOT NULL,
+    "user_id" VARCHAR(26) NOT NULL,
+    "todo_id" VARCHAR(26),
+    "project_id" VARCHAR(26),
+    "permission" "share_permission_" NOT NULL DEFAULT 'viewer',
+    "status" "share_status_" NOT NULL DEFAULT 'pending',
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    "updated_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    CONSTRAINT "fk_share_owner_id_" FOREIGN KEY ("owner_id") REFERENCES "user_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_share_user_id_" FOREIGN KEY ("user_id") REFERENCES "user_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_share_todo_id_" FOREIGN KEY ("todo_id") REFERENCES "todo_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_share_project_id_" FOREIGN KEY ("project_id") REFERENCES "project_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "chk_share_target_" CHECK (("todo_id" IS NULL) <> ("project_id" IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_share_user_id_todo_id_" ON "share_" ("user_id", "todo_id");
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_share_user_id_project_id_" ON "share_" ("user_id", "project_id");
+
+CREATE INDEX IF NOT EXISTS "idx_share_owner_id_" ON "share_" ("owner_id");
+
+DROP TRIGGER IF EXISTS "share_updated_at_" ON "share_";
+
+CREATE TRIGGER "share_updated_at_" BEFORE
+UPDATE
+    ON "share_" FOR EACH ROW EXECUTE FUNCTION updated_at();
+
+DO $$
+BEGIN
+IF NOT EXISTS (
+    SELECT
+        1
+    FROM
+        pg_type
+    WHERE
+        typname = 'workspace_role_'
+) THEN
+CREATE TYPE "workspace_role_" AS ENUM ('owner', 'admin', 'member', 'guest');
+
+END IF;
+
+END;
+
+$$;
+
+CREATE TABLE IF NOT EXISTS "workspace_" (
+    "id" VARCHAR(26) PRIMARY KEY DEFAULT gen_ulid(),
+    "name" VARCHAR(100) NOT NULL,
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    "updated_at" BIGINT NOT NULL DEFAULT get_epoch()
+);
+
+DROP TRIGGER IF EXISTS "workspace_updated_at_" ON "workspace_";
+
+CREATE TRIGGER "workspace_updated_at_" BEFORE
+UPDATE
+    ON "workspace_" FOR EACH ROW EXECUTE FUNCTION updated_at();
+
+CREATE TABLE IF NOT EXISTS "workspace_member_" (
+    "workspace_id" VARCHAR(26) NOT NULL,
+    "user_id" VARCHAR(26) NOT NULL,
+    "role" "workspace_role_" NOT NULL DEFAULT 'member',
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    PRIMARY KEY ("workspace_id", "user_id"),
+    CONSTRAINT "fk_workspace_member_workspace_id_" FOREIGN KEY ("workspace_id") REFERENCES "workspace_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_workspace_member_user_id_" FOREIGN KEY ("user_id") REFERENCES "user_" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_workspace_member_user_id_" ON "workspace_member_" ("user_id");
+
+CREATE TABLE IF NOT EXISTS "workspace_invitation_" (
+    "id" VARCHAR(26) PRIMARY KEY DEFAULT gen_ulid(),
+    "workspace_id" VARCHAR(26) NOT NULL,
+    "user_id" VARCHAR(26) NOT NULL,
+    "invited_by" VARCHAR(26) NOT NULL,
+    "role" "workspace_role_" NOT NULL DEFAULT 'member',
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    CONSTRAINT "fk_workspace_invitation_workspace_id_" FOREIGN KEY ("workspace_id") REFERENCES "workspace_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_workspace_invitation_user_id_" FOREIGN KEY ("user_id") REFERENCES "user_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_workspace_invitation_invited_by_" FOREIGN KEY ("invited_by") REFERENCES "user_" ("id") ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_workspace_invitation_user_id_workspace_id_" ON "workspace_invitation_" ("user_id", "workspace_id");
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "workspace_id" VARCHAR(26) CONSTRAINT "fk_todo_workspace_id_" REFERENCES "workspace_" ("id") ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS "idx_todo_workspace_id_" ON "todo_" ("workspace_id");
+
+DROP INDEX IF EXISTS "idx_todo_user_id_position_";
+
+WITH "ranked_" AS (
+    SELECT "id", row_number() OVER (PARTITION BY "workspace_id" ORDER BY "position", "id") AS "rank"
+    FROM "todo_"
+    WHERE "workspace_id" IN (
+        SELECT "workspace_id"
+        FROM "todo_"
+        WHERE "workspace_id" IS NOT NULL
+        GROUP BY "workspace_id", "position"
+        HAVING count(*) > 1
+    )
+)
+UPDATE "todo_" SET "position" = 'c'
+    || substr('0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz', ("ranked_"."rank" / 3844 % 62)::INTEGER + 1, 1)
+    || substr('0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz', ("ranked_"."rank" / 62 % 62)::INTEGER + 1, 1)
+    || substr('0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz', ("ranked_"."rank" % 62)::INTEGER + 1, 1)
+FROM "ranked_"
+WHERE "todo_"."id" = "ranked_"."id";
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_todo_personal_position_" ON "todo_" ("user_id", "position") WHERE "workspace_id" IS NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_todo_workspace_id_position_" ON "todo_" ("workspace_id", "position") WHERE "workspace_id" IS NOT NULL;
+
+ALTER TABLE "todo_" ADD COLUMN IF NOT EXISTS "assignee_id" VARCHAR(26) CONSTRAINT "fk_todo_assignee_id_" REFERENCES "user_" ("id") ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS "idx_todo_assignee_id_" ON "todo_" ("assignee_id");
+
+CREATE TABLE IF NOT EXISTS "todo_comment_" (
+    "id" VARCHAR(26) PRIMARY KEY DEFAULT gen_ulid(),
+    "todo_id" VARCHAR(26) NOT NULL,
+    "user_id" VARCHAR(26) NOT NULL,
+    "body" TEXT NOT NULL,
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    "edited_at" BIGINT,
+    CONSTRAINT "fk_todo_comment_todo_id_" FOREIGN KEY ("todo_id") REFERENCES "todo_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_todo_comment_user_id_" FOREIGN KEY ("user_id") REFERENCES "user_" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_todo_comment_todo_id_" ON "todo_comment_" ("todo_id", "id");
+
+CREATE TABLE IF NOT EXISTS "todo_attachment_" (
+    "id" VARCHAR(26) PRIMARY KEY DEFAULT gen_ulid(),
+    "todo_id" VARCHAR(26) NOT NULL,
+    "user_id" VARCHAR(26) NOT NULL,
+    "file_name" VARCHAR(255) NOT NULL,
+    "content_type" VARCHAR(255) NOT NULL,
+    "size" BIGINT NOT NULL,
+    "storage_key" VARCHAR(255) NOT NULL,
+    "created_at" BIGINT NOT NULL DEFAULT get_epoch(),
+    CONSTRAINT "fk_todo_attachment_todo_id_" FOREIGN KEY ("todo_id") REFERENCES "todo_" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_todo_attachment_user_id_" FOREIGN KEY ("user_id") REFERENCES "user_" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_todo_attachment_todo_id_" ON "todo_attachment_" ("todo_id", "id");
@@ -88,6 +88,20 @@ pub struct Env {
     #[serde(deserialize_with = "deserialize_base64")]
     pub reauth_token_public_key: Arc<Vec<u8>>,
 
+    #[validate(length(
+        min = 1,
+        message = "attachment token private key is required and cannot be empty"
+    ))]
+    #[serde(deserialize_with = "deserialize_base64")]
+    pub attachment_token_private_key: Arc<Vec<u8>>,
+
+    #[validate(length(
+        min = 1,
+        message = "attachment token public key is required and cannot be empty"
+    ))]
+    #[serde(deserialize_with = "deserialize_base64")]
+    pub attachment_token_public_key: Arc<Vec<u8>>,
+
     #[validate(range(
         min = 172_800,
         message = "refresh token expiration must be greater than 172,800 seconds (2 Days)"
@@ -112,12 +126,130 @@ pub struct Env {
     ))]
     pub reauth_token_expiration: usize,
 
+    #[validate(range(
+        min = 1,
+        max = 3_600,
+        message = "attachment token expiration must be between 1 and 3,600 seconds"
+    ))]
+    #[serde(default = "default_attachment_token_expiration")]
+    pub attachment_token_expiration: usize,
+
     #[validate(range(
         min = 8080,
         max = 8090,
         message = "please provide a valid port number between 8080 and 8090 (inclusive)"
     ))]
     pub port: u16,
+
+    #[validate(range(
+        min = 86_400,
+        message = "todo trash retention must be at least 86,400 seconds (1 Day)"
+    ))]
+    #[serde(default = "default_todo_trash_retention")]
+    pub todo_trash_retention: u64,
+
+    #[validate(range(
+        min = 60,
+        message = "todo trash purge interval must be at least 60 seconds"
+    ))]
+    #[serde(default = "default_todo_trash_purge_interval")]
+    pub todo_trash_purge_interval: u64,
+
+    #[validate(range(min = 60, message = "idempotency key ttl must be at least 60 seconds"))]
+    #[serde(default = "default_idempotency_key_ttl")]
+    pub idempotency_key_ttl: u64,
+
+    #[validate(range(
+        min = 5,
+        max = 3_600,
+        message = "todo undo ttl must be between 5 and 3,600 seconds"
+    ))]
+    #[serde(default = "default_todo_undo_ttl")]
+    pub todo_undo_ttl: u64,
+
+    #[validate(range(
+        min = 1,
+        max = 104_857_600,
+        message = "attachment max size must be between 1 byte and 104,857,600 bytes (100 MiB)"
+    ))]
+    #[serde(default = "default_attachment_max_size")]
+    pub attachment_max_size: u64,
+
+    /// Seconds an upload may take, in place of the timeout of other requests
+    #[validate(range(
+        min = 10,
+        max = 3_600,
+        message = "attachment upload timeout must be between 10 and 3,600 seconds"
+    ))]
+    #[serde(default = "default_attachment_upload_timeout")]
+    pub attachment_upload_timeout: u64,
+
+    /// Comma separated list of the content types an attachment may have
+    #[validate(length(min = 1, message = "attachment allowed types cannot be empty"))]
+    #[serde(
+        default = "default_attachment_allowed_types",
+        deserialize_with = "deserialize_arc_str"
+    )]
+    pub attachment_allowed_types: Arc<str>,
+
+    #[validate(custom(function = "verify::attachment_storage"))]
+    #[serde(
+        default = "default_attachment_storage",
+        deserialize_with = "deserialize_arc_str"
+    )]
+    pub attachment_storage: Arc<str>,
+
+    #[serde(
+        default = "default_attachment_local_dir",
+        deserialize_with = "deserialize_arc_str"
+    )]
+    pub attachment_local_dir: Arc<str>,
+
+    pub attachment_s3_endpoint: Option<String>,
+    pub attachment_s3_bucket: Option<String>,
+    pub attachment_s3_region: Option<String>,
+    pub attachment_s3_access_key: Option<String>,
+    pub attachment_s3_secret_key: Option<String>,
+}
+
+fn default_todo_trash_retention() -> u64 {
+    2_592_000
+}
+
+fn default_todo_trash_purge_interval() -> u64 {
+    3_600
+}
+
+fn default_idempotency_key_ttl() -> u64 {
+    86_400
+}
+
+fn default_todo_undo_ttl() -> u64 {
+    30
+}
+
+fn default_attachment_token_expiration() -> usize {
+    300
+}
+
+fn default_attachment_max_size() -> u64 {
+    10_485_760
+}
+
+fn default_attachment_upload_timeout() -> u64 {
+    300
+}
+
+fn default_attachment_allowed_types() -> Arc<str> {
+    Arc::from("image/png,image/jpeg,image/gif,image/webp,application/pdf,text/plain")
+}
+
+fn default_attachment_storage() -> Arc<str> {
+    Arc::from("local")
+}
+
+fn default_attachment_local_dir() -> Arc<str> {
+    Arc::from("uploads")
 }
 
 impl Default for Env {
@@ -172,4 +304,12 @@ impl Env {
     }
 }
 
+impl Env {
+    pub fn attachment_type_allowed(&self, content_type: &str) -> bool {
+        self.attachment_allowed_types
+            .split(',')
+            .any(|allowed| allowed.trim().eq_ignore_ascii_case(content_type))
+    }
+}
+
 pub static ENV: Lazy<Env> = Lazy::new(Env::new);
@@ -1,4 +1,5 @@
 use super::ENV;
+use crate::storage::FileStorage;
 use log::error;
 use redis::{aio::MultiplexedConnection, Client as RedisClient, RedisError};
 use sea_orm::{Database, DatabaseConnection};
@@ -7,6 +8,7 @@ use sea_orm::{Database, DatabaseConnection};
 pub struct AppState {
     pub db: DatabaseConnection,
     pub rd: RedisClient,
+    pub storage: FileStorage,
 }
 
 impl AppState {
@@ -24,7 +26,12 @@ impl AppState {
             std::process::exit(1);
         });
 
-        Self { db, rd }
+        let storage = FileStorage::from_env().unwrap_or_else(|err| {
+            error!("{}", err);
+            std::process::exit(1);
+        });
+
+        Self { db, rd, storage }
     }
 }
 
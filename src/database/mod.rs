@@ -1,3 +1,12 @@
+pub mod project;
 pub mod session;
+pub mod share;
+pub mod subtask;
+pub mod tag;
 pub mod todo;
+pub mod todo_attachment;
+pub mod todo_comment;
+pub mod todo_event;
+pub mod undo;
 pub mod user;
+pub mod workspace;
@@ -0,0 +1,109 @@
+use crate::{
+    entity::{
+        prelude::{Project, Todo},
+        project, todo,
+    },
+    model::project::{CreateProjectReq, DeleteProjectMode},
+    utils::time,
+};
+use sea_orm::{sea_query::Expr, *};
+
+pub async fn create(
+    user_id: String,
+    data: CreateProjectReq,
+    db: &DatabaseConnection,
+) -> Result<project::Model, DbErr> {
+    Project::insert(project::ActiveModel {
+        user_id: Set(user_id),
+        name: Set(data.name),
+        archived: Set(false),
+        ..Default::default()
+    })
+    .exec_with_returning(db)
+    .await
+}
+
+pub async fn find_by_user_id(
+    user_id: String,
+    include_archived: bool,
+    db: &DatabaseConnection,
+) -> Result<Vec<project::Model>, DbErr> {
+    let mut query = Project::find().filter(project::Column::UserId.eq(user_id));
+    if !include_archived {
+        query = query.filter(project::Column::Archived.eq(false));
+    }
+
+    query.order_by_asc(project::Column::Name).all(db).await
+}
+
+pub async fn ensure_owned<C>(id: &str, user_id: &str, db: &C) -> Result<project::Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Project::find_by_id(id)
+        .filter(project::Column::UserId.eq(user_id))
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Project not found for the given id",
+        )))
+}
+
+pub async fn rename(
+    id: String,
+    user_id: String,
+    name: String,
+    db: &DatabaseConnection,
+) -> Result<project::Model, DbErr> {
+    Project::update(project::ActiveModel {
+        id: Set(id),
+        name: Set(name),
+        ..Default::default()
+    })
+    .filter(project::Column::UserId.eq(user_id))
+    .exec(db)
+    .await
+}
+
+pub async fn archive(
+    id: String,
+    user_id: String,
+    archived: bool,
+    db: &DatabaseConnection,
+) -> Result<project::Model, DbErr> {
+    Project::update(project::ActiveModel {
+        id: Set(id),
+        archived: Set(archived),
+        ..Default::default()
+    })
+    .filter(project::Column::UserId.eq(user_id))
+    .exec(db)
+    .await
+}
+
+pub async fn delete(
+    id: String,
+    user_id: String,
+    mode: DeleteProjectMode,
+    db: &DatabaseConnection,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    ensure_owned(&id, &user_id, &txn).await?;
+
+    // todo_.project_id is ON DELETE SET NULL, so the inbox mode only has to
+    // remove the project itself, cascaded todos go to the trash
+    if mode == DeleteProjectMode::Cascade {
+        Todo::update_many()
+            .col_expr(todo::Column::DeletedAt, Expr::value(time::now()))
+            .filter(todo::Column::ProjectId.eq(&id))
+            .filter(todo::Column::UserId.eq(&user_id))
+            .filter(todo::Column::DeletedAt.is_null())
+            .exec(&txn)
+            .await?;
+    }
+
+    Project::delete_by_id(id).exec(&txn).await?;
+
+    txn.commit().await
+}
@@ -0,0 +1,356 @@
+use crate::{
+    database,
+    entity::{
+        prelude::{Project, Share, Todo, User},
+        project,
+        sea_orm_active_enums::{SharePermission, ShareStatus},
+        share, todo, user,
+    },
+    model::share::{CreateShareReq, ShareInvitation, SharedBy},
+};
+use sea_orm::{
+    sea_query::{Expr, Query},
+    *,
+};
+use std::collections::HashMap;
+
+fn allows(granted: SharePermission, required: SharePermission) -> bool {
+    matches!(
+        (granted, required),
+        (SharePermission::Editor, _) | (SharePermission::Viewer, SharePermission::Viewer)
+    )
+}
+
+fn accepted_by(user_id: &str) -> Condition {
+    Condition::all()
+        .add(share::Column::UserId.eq(user_id))
+        .add(share::Column::Status.eq(ShareStatus::Accepted))
+}
+
+pub fn accessible(user_id: &str) -> Condition {
+    let shared = |column: share::Column| {
+        Query::select()
+            .column(column)
+            .from(Share)
+            .cond_where(accepted_by(user_id))
+            .and_where(Expr::col(column).is_not_null())
+            .to_owned()
+    };
+
+    Condition::any()
+        .add(todo::Column::UserId.eq(user_id))
+        .add(todo::Column::AssigneeId.eq(user_id))
+        .add(todo::Column::Id.in_subquery(shared(share::Column::TodoId)))
+        .add(todo::Column::ProjectId.in_subquery(shared(share::Column::ProjectId)))
+}
+
+pub async fn authorize<C>(
+    todo_id: &str,
+    user_id: &str,
+    permission: SharePermission,
+    db: &C,
+) -> Result<todo::Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let not_found = || DbErr::RecordNotFound(String::from("Todo not found for the given id"));
+
+    let todo = Todo::find_by_id(todo_id)
+        .one(db)
+        .await?
+        .ok_or_else(not_found)?;
+
+    if let Some(workspace_id) = &todo.workspace_id {
+        let role = match database::workspace::find_membership(workspace_id, user_id, db).await {
+            Ok(member) => member.role,
+            Err(DbErr::RecordNotFound(_)) => return Err(not_found()),
+            Err(err) => return Err(err),
+        };
+        let granted = if todo.user_id == user_id || role.can_write() {
+            SharePermission::Editor
+        } else {
+            SharePermission::Viewer
+        };
+
+        return if allows(granted, permission) {
+            Ok(todo)
+        } else {
+            Err(not_found())
+        };
+    }
+
+    if todo.user_id == user_id {
+        return Ok(todo);
+    }
+    if todo.assignee_id.as_deref() == Some(user_id) && permission == SharePermission::Viewer {
+        return Ok(todo);
+    }
+
+    let mut target = Condition::any().add(share::Column::TodoId.eq(todo_id));
+    if let Some(project_id) = &todo.project_id {
+        target = target.add(share::Column::ProjectId.eq(project_id));
+    }
+
+    let granted = Share::find()
+        .filter(accepted_by(user_id))
+        .filter(target)
+        .all(db)
+        .await?
+        .into_iter()
+        .any(|share| allows(share.permission, permission));
+
+    if granted {
+        Ok(todo)
+    } else {
+        Err(not_found())
+    }
+}
+
+pub async fn find_shared_by(
+    user_id: &str,
+    todos: &[todo::Model],
+    db: &DatabaseConnection,
+) -> Result<HashMap<String, SharedBy>, DbErr> {
+    let shared: Vec<&todo::Model> = todos
+        .iter()
+        .filter(|todo| todo.user_id != user_id)
+        .collect();
+    if shared.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let shares = Share::find()
+        .filter(accepted_by(user_id))
+        .filter(
+            Condition::any()
+                .add(share::Column::TodoId.is_in(shared.iter().map(|todo| todo.id.clone())))
+                .add(
+                    share::Column::ProjectId
+                        .is_in(shared.iter().filter_map(|todo| todo.project_id.clone())),
+                ),
+        )
+        .all(db)
+        .await?;
+
+    let owners: HashMap<String, String> = User::find()
+        .filter(user::Column::Id.is_in(shared.iter().map(|todo| todo.user_id.clone())))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.name))
+        .collect();
+
+    let mut result = HashMap::with_capacity(shared.len());
+    for todo in shared {
+        let permission = shares
+            .iter()
+            .filter(|share| {
+                share.todo_id.as_ref() == Some(&todo.id)
+                    || (share.project_id.is_some() && share.project_id == todo.project_id)
+            })
+            .map(|share| share.permission)
+            .reduce(|widest, permission| {
+                if allows(permission, widest) {
+                    permission
+                } else {
+                    widest
+                }
+            });
+
+        if let Some(permission) = permission {
+            result.insert(
+                todo.id.clone(),
+                SharedBy {
+                    owner_id: todo.user_id.clone(),
+                    owner_name: owners.get(&todo.user_id).cloned().unwrap_or_default(),
+                    permission,
+                },
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+pub async fn invite(
+    owner_id: String,
+    user_id: String,
+    data: CreateShareReq,
+    db: &DatabaseConnection,
+) -> Result<share::Model, DbErr> {
+    let txn = db.begin().await?;
+
+    if let Some(todo_id) = &data.todo_id {
+        database::todo::find_owned(todo_id, &owner_id, &txn).await?;
+    }
+    if let Some(project_id) = &data.project_id {
+        database::project::ensure_owned(project_id, &owner_id, &txn).await?;
+    }
+
+    let existing = Share::find()
+        .filter(share::Column::UserId.eq(&user_id))
+        .apply_if(data.todo_id.clone(), |query, todo_id| {
+            query.filter(share::Column::TodoId.eq(todo_id))
+        })
+        .apply_if(data.project_id.clone(), |query, project_id| {
+            query.filter(share::Column::ProjectId.eq(project_id))
+        })
+        .one(&txn)
+        .await?;
+
+    let share = match existing {
+        Some(existing) => {
+            let status = match existing.status {
+                ShareStatus::Declined => ShareStatus::Pending,
+                status => status,
+            };
+
+            Share::update(share::ActiveModel {
+                id: Set(existing.id),
+                permission: Set(data.permission),
+                status: Set(status),
+                ..Default::default()
+            })
+            .exec(&txn)
+            .await?
+        }
+        None => {
+            Share::insert(share::ActiveModel {
+                owner_id: Set(owner_id),
+                user_id: Set(user_id),
+                todo_id: Set(data.todo_id),
+                project_id: Set(data.project_id),
+                permission: Set(data.permission),
+                status: Set(ShareStatus::Pending),
+                ..Default::default()
+            })
+            .exec_with_returning(&txn)
+            .await?
+        }
+    };
+
+    txn.commit().await?;
+    Ok(share)
+}
+
+pub async fn find_by_owner_id(
+    owner_id: String,
+    db: &DatabaseConnection,
+) -> Result<Vec<share::Model>, DbErr> {
+    Share::find()
+        .filter(share::Column::OwnerId.eq(owner_id))
+        .order_by_desc(share::Column::Id)
+        .all(db)
+        .await
+}
+
+pub async fn find_invitations(
+    user_id: String,
+    db: &DatabaseConnection,
+) -> Result<Vec<ShareInvitation>, DbErr> {
+    let shares = Share::find()
+        .filter(share::Column::UserId.eq(user_id))
+        .filter(share::Column::Status.eq(ShareStatus::Pending))
+        .order_by_desc(share::Column::Id)
+        .all(db)
+        .await?;
+
+    let owners: HashMap<String, String> = User::find()
+        .filter(user::Column::Id.is_in(shares.iter().map(|share| share.owner_id.clone())))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.name))
+        .collect();
+
+    let mut titles: HashMap<String, String> = Todo::find()
+        .filter(todo::Column::Id.is_in(shares.iter().filter_map(|share| share.todo_id.clone())))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|todo| (todo.id, todo.title))
+        .collect();
+    titles.extend(
+        Project::find()
+            .filter(
+                project::Column::Id
+                    .is_in(shares.iter().filter_map(|share| share.project_id.clone())),
+            )
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|project| (project.id, project.name)),
+    );
+
+    Ok(shares
+        .into_iter()
+        .map(|share| {
+            let target = share.todo_id.as_ref().or(share.project_id.as_ref());
+            ShareInvitation {
+                owner_name: owners.get(&share.owner_id).cloned().unwrap_or_default(),
+                title: target
+                    .and_then(|id| titles.get(id))
+                    .cloned()
+                    .unwrap_or_default(),
+                share,
+            }
+        })
+        .collect())
+}
+
+pub async fn respond(
+    id: String,
+    user_id: String,
+    accept: bool,
+    db: &DatabaseConnection,
+) -> Result<share::Model, DbErr> {
+    Share::update(share::ActiveModel {
+        id: Set(id),
+        status: Set(if accept {
+            ShareStatus::Accepted
+        } else {
+            ShareStatus::Declined
+        }),
+        ..Default::default()
+    })
+    .filter(share::Column::UserId.eq(user_id))
+    .filter(share::Column::Status.eq(ShareStatus::Pending))
+    .exec(db)
+    .await
+}
+
+pub async fn update_permission(
+    id: String,
+    owner_id: String,
+    permission: SharePermission,
+    db: &DatabaseConnection,
+) -> Result<share::Model, DbErr> {
+    Share::update(share::ActiveModel {
+        id: Set(id),
+        permission: Set(permission),
+        ..Default::default()
+    })
+    .filter(share::Column::OwnerId.eq(owner_id))
+    .exec(db)
+    .await
+}
+
+pub async fn delete(id: String, user_id: String, db: &DatabaseConnection) -> Result<(), DbErr> {
+    let result = Share::delete_many()
+        .filter(share::Column::Id.eq(id))
+        .filter(
+            Condition::any()
+                .add(share::Column::OwnerId.eq(&user_id))
+                .add(share::Column::UserId.eq(&user_id)),
+        )
+        .exec(db)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(DbErr::RecordNotFound(String::from(
+            "Share not found for the given id",
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::{
+    database,
+    entity::{prelude::Subtask, subtask, todo},
+    model::{
+        subtask::{AddSubtaskReq, ReorderSubtaskReq},
+        todo::SubtaskCount,
+    },
+};
+use sea_orm::{sea_query::Expr, *};
+use std::collections::{HashMap, HashSet};
+
+pub async fn add(
+    user_id: String,
+    data: AddSubtaskReq,
+    db: &DatabaseConnection,
+) -> Result<subtask::Model, DbErr> {
+    database::todo::find_owned(&data.todo_id, &user_id, db).await?;
+
+    let position: Option<i32> = Subtask::find()
+        .select_only()
+        .column_as(subtask::Column::Position.max(), "position")
+        .filter(subtask::Column::TodoId.eq(&data.todo_id))
+        .into_tuple()
+        .one(db)
+        .await?
+        .flatten();
+
+    Subtask::insert(subtask::ActiveModel {
+        todo_id: Set(data.todo_id),
+        title: Set(data.title),
+        completed: Set(false),
+        position: Set(position.map_or(0, |position| position + 1)),
+        ..Default::default()
+    })
+    .exec_with_returning(db)
+    .await
+}
+
+pub async fn find_by_todo_id(
+    todo_id: String,
+    user_id: String,
+    db: &DatabaseConnection,
+) -> Result<Vec<subtask::Model>, DbErr> {
+    database::todo::find_owned(&todo_id, &user_id, db).await?;
+
+    Subtask::find()
+        .filter(subtask::Column::TodoId.eq(todo_id))
+        .order_by_asc(subtask::Column::Position)
+        .all(db)
+        .await
+}
+
+async fn find_owned<C>(id: &str, user_id: &str, db: &C) -> Result<subtask::Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Subtask::find_by_id(id)
+        .inner_join(todo::Entity)
+        .filter(todo::Column::UserId.eq(user_id))
+        .filter(todo::Column::DeletedAt.is_null())
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Subtask not found for the given id",
+        )))
+}
+
+pub async fn toggle(
+    id: String,
+    user_id: String,
+    db: &DatabaseConnection,
+) -> Result<subtask::Model, DbErr> {
+    let subtask = find_owned(&id, &user_id, db).await?;
+
+    subtask::ActiveModel {
+        id: Set(id),
+        completed: Set(!subtask.completed),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+pub async fn reorder(
+    user_id: String,
+    data: ReorderSubtaskReq,
+    db: &DatabaseConnection,
+) -> Result<Vec<subtask::Model>, DbErr> {
+    let txn = db.begin().await?;
+
+    database::todo::find_owned(&data.todo_id, &user_id, &txn).await?;
+
+    let current: HashSet<String> = Subtask::find()
+        .select_only()
+        .column(subtask::Column::Id)
+        .filter(subtask::Column::TodoId.eq(&data.todo_id))
+        .into_tuple()
+        .all(&txn)
+        .await?
+        .into_iter()
+        .collect();
+    let requested: HashSet<&String> = data.ids.iter().collect();
+
+    if requested.len() != data.ids.len()
+        || current.len() != data.ids.len()
+        || !data.ids.iter().all(|id| current.contains(id))
+    {
+        return Err(DbErr::RecordNotFound(String::from(
+            "The given ids do not match the subtasks of the todo",
+        )));
+    }
+
+    for (position, id) in data.ids.into_iter().enumerate() {
+        Subtask::update_many()
+            .col_expr(subtask::Column::Position, Expr::value(position as i32))
+            .filter(subtask::Column::Id.eq(id))
+            .exec(&txn)
+            .await?;
+    }
+
+    let subtasks = Subtask::find()
+        .filter(subtask::Column::TodoId.eq(&data.todo_id))
+        .order_by_asc(subtask::Column::Position)
+        .all(&txn)
+        .await?;
+
+    txn.commit().await?;
+    Ok(subtasks)
+}
+
+pub async fn delete(id: String, user_id: String, db: &DatabaseConnection) -> Result<(), DbErr> {
+    find_owned(&id, &user_id, db).await?;
+
+    Subtask::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+pub async fn complete_all<C>(todo_id: &str, db: &C) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    Subtask::update_many()
+        .col_expr(subtask::Column::Completed, Expr::value(true))
+        .filter(subtask::Column::TodoId.eq(todo_id))
+        .filter(subtask::Column::Completed.eq(false))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+#[derive(FromQueryResult)]
+struct SubtaskCountRow {
+    todo_id: String,
+    done: i64,
+    total: i64,
+}
+
+pub async fn count_by_todo_ids(
+    todo_ids: Vec<String>,
+    db: &DatabaseConnection,
+) -> Result<HashMap<String, SubtaskCount>, DbErr> {
+    if todo_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows = Subtask::find()
+        .select_only()
+        .column(subtask::Column::TodoId)
+        .column_as(
+            Expr::cust("COUNT(*) FILTER (WHERE \"subtask_\".\"completed\")"),
+            "done",
+        )
+        .column_as(subtask::Column::Id.count(), "total")
+        .filter(subtask::Column::TodoId.is_in(todo_ids))
+        .group_by(subtask::Column::TodoId)
+        .into_model::<SubtaskCountRow>()
+        .all(db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.todo_id,
+                SubtaskCount {
+                    done: row.done,
+                    total: row.total,
+                },
+            )
+        })
+        .collect())
+}
@@ -0,0 +1,129 @@
+use crate::{
+    database,
+    entity::{
+        prelude::{Tag, TodoTag},
+        tag, todo_tag,
+    },
+    model::tag::{CreateTagReq, TodoTagReq, UpdateTagReq},
+};
+use sea_orm::*;
+
+pub async fn create(
+    user_id: String,
+    data: CreateTagReq,
+    db: &DatabaseConnection,
+) -> Result<tag::Model, DbErr> {
+    Tag::insert(tag::ActiveModel {
+        user_id: Set(user_id),
+        name: Set(data.name),
+        color: Set(data.color),
+        ..Default::default()
+    })
+    .exec_with_returning(db)
+    .await
+}
+
+pub async fn find_by_user_id(
+    user_id: String,
+    db: &DatabaseConnection,
+) -> Result<Vec<tag::Model>, DbErr> {
+    Tag::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .order_by_asc(tag::Column::Name)
+        .all(db)
+        .await
+}
+
+pub async fn update(
+    user_id: String,
+    data: UpdateTagReq,
+    db: &DatabaseConnection,
+) -> Result<tag::Model, DbErr> {
+    let mut update = tag::ActiveModel {
+        id: Set(data.id),
+        ..Default::default()
+    };
+
+    if let Some(name) = data.name {
+        update.name = Set(name);
+    }
+    if let Some(color) = data.color {
+        update.color = Set(Some(color));
+    }
+
+    Tag::update(update)
+        .filter(tag::Column::UserId.eq(user_id))
+        .exec(db)
+        .await
+}
+
+pub async fn delete(id: String, user_id: String, db: &DatabaseConnection) -> Result<(), DbErr> {
+    let result = Tag::delete_many()
+        .filter(tag::Column::Id.eq(id))
+        .filter(tag::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(DbErr::RecordNotFound(String::from(
+            "Tag not found for the given id",
+        )));
+    }
+
+    Ok(())
+}
+
+pub async fn attach(
+    user_id: String,
+    data: TodoTagReq,
+    db: &DatabaseConnection,
+) -> Result<(), DbErr> {
+    ensure_owned(&user_id, &data, db).await?;
+
+    TodoTag::insert(todo_tag::ActiveModel {
+        todo_id: Set(data.todo_id),
+        tag_id: Set(data.tag_id),
+    })
+    .on_conflict(
+        sea_query::OnConflict::columns([todo_tag::Column::TodoId, todo_tag::Column::TagId])
+            .do_nothing()
+            .to_owned(),
+    )
+    .do_nothing()
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn detach(
+    user_id: String,
+    data: TodoTagReq,
+    db: &DatabaseConnection,
+) -> Result<(), DbErr> {
+    ensure_owned(&user_id, &data, db).await?;
+
+    TodoTag::delete_by_id((data.todo_id, data.tag_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+async fn ensure_owned(
+    user_id: &str,
+    data: &TodoTagReq,
+    db: &DatabaseConnection,
+) -> Result<(), DbErr> {
+    database::todo::find_owned(&data.todo_id, user_id, db).await?;
+
+    Tag::find_by_id(&data.tag_id)
+        .filter(tag::Column::UserId.eq(user_id))
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Tag not found for the given id",
+        )))?;
+
+    Ok(())
+}
@@ -1,41 +1,491 @@
 use crate::{
+    database,
     entity::{
-        prelude::Todo,
+        prelude::{Subtask, Todo, TodoTag, WorkspaceMember},
+        sea_orm_active_enums::{Priority, TodoAction},
+        subtask,
         todo::{self},
+        todo_tag, workspace_member,
     },
-    model::todo::{CreateTodoReq, PaginatedTodo, UpdateTodoReq},
-    utils::paginate::Paginator,
+    model::{
+        recurrence::Recurrence,
+        todo::{
+            Assignee, CreateTodoReq, DueFilter, PaginatedTodo, SortOrder, TodoChange, TodoFilter,
+            TodoHighlight, TodoItem, TodoSearchHit, TodoSearchQuery, TodoSearchResult, TodoSort,
+            UpdateTodoReq, INBOX, ME,
+        },
+        undo::UndoRecord,
+    },
+    utils::{
+        paginate::{Cursor, Direction, Paginator},
+        position::key_between,
+        time::{self, SECONDS_IN_DAY},
+    },
+};
+use sea_orm::{
+    prelude::Json,
+    sea_query::{Alias, Expr, NullOrdering, Query, SimpleExpr},
+    *,
 };
-use sea_orm::*;
+use std::collections::HashMap;
 
-pub async fn create(
+pub async fn create<C>(
     user_id: String,
+    workspace_id: Option<String>,
+    session_id: Option<String>,
     data: CreateTodoReq,
-    db: &DatabaseConnection,
-) -> Result<todo::Model, DbErr> {
-    Todo::insert(todo::ActiveModel {
-        title: Set(data.title),
-        user_id: Set(user_id),
-        content: Set(data.content),
-        completed: Set(false),
-        ..Default::default()
-    })
-    .exec_with_returning(db)
-    .await
+    db: &C,
+) -> Result<todo::Model, DbErr>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let txn = db.begin().await?;
+
+    if let Some(project_id) = &data.project_id {
+        database::project::ensure_owned(project_id, &user_id, &txn).await?;
+    }
+
+    let todo = insert_last(
+        todo::ActiveModel {
+            title: Set(data.title),
+            user_id: Set(user_id.clone()),
+            content: Set(data.content),
+            completed: Set(false),
+            due_at: Set(data.due_at),
+            priority: Set(data.priority.unwrap_or_default()),
+            project_id: Set(data.project_id),
+            recurrence: Set(recurrence_to_json(data.recurrence)?),
+            workspace_id: Set(workspace_id),
+            ..Default::default()
+        },
+        &txn,
+    )
+    .await?;
+    database::todo_event::record(
+        TodoAction::Create,
+        &user_id,
+        None,
+        Some(&todo),
+        session_id,
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+    Ok(todo)
 }
 
-pub async fn find_by_id(id: String, db: &DatabaseConnection) -> Result<Option<todo::Model>, DbErr> {
-    Todo::find_by_id(id).one(db).await
+const POSITION_ATTEMPTS: usize = 5;
+
+fn is_position_conflict(err: &DbErr) -> bool {
+    matches!(
+        err.sql_err(),
+        Some(SqlErr::UniqueConstraintViolation(message)) if message.contains("position")
+    )
 }
 
-pub async fn find_by_user_id(
+fn ordered_with(user_id: &str, workspace_id: Option<&str>) -> Condition {
+    match workspace_id {
+        Some(workspace_id) => Condition::all().add(todo::Column::WorkspaceId.eq(workspace_id)),
+        None => Condition::all()
+            .add(todo::Column::UserId.eq(user_id))
+            .add(todo::Column::WorkspaceId.is_null()),
+    }
+}
+
+async fn next_position<C>(
+    user_id: &str,
+    workspace_id: Option<&str>,
+    db: &C,
+) -> Result<String, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let last = Todo::find()
+        .select_only()
+        .column(todo::Column::Position)
+        .filter(ordered_with(user_id, workspace_id))
+        .order_by_desc(todo::Column::Position)
+        .into_tuple::<String>()
+        .one(db)
+        .await?;
+
+    key_between(last.as_deref(), None).ok_or(DbErr::Custom(String::from(
+        "Could not find a position after the last todo",
+    )))
+}
+
+async fn insert_last<C>(model: todo::ActiveModel, db: &C) -> Result<todo::Model, DbErr>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let user_id = model
+        .user_id
+        .clone()
+        .take()
+        .ok_or(DbErr::Custom(String::from(
+            "Todo user id is required to insert",
+        )))?;
+    let workspace_id = model.workspace_id.clone().take().flatten();
+
+    let mut attempt = 1;
+    loop {
+        let savepoint = db.begin().await?;
+        let mut model = model.clone();
+        model.position = Set(next_position(&user_id, workspace_id.as_deref(), &savepoint).await?);
+
+        match Todo::insert(model).exec_with_returning(&savepoint).await {
+            Ok(todo) => {
+                savepoint.commit().await?;
+                return Ok(todo);
+            }
+            Err(err) if attempt < POSITION_ATTEMPTS && is_position_conflict(&err) => {
+                savepoint.rollback().await?;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+pub async fn find_owned<C>(id: &str, user_id: &str, db: &C) -> Result<todo::Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Todo::find_by_id(id)
+        .filter(todo::Column::UserId.eq(user_id))
+        .filter(todo::Column::DeletedAt.is_null())
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Todo not found for the given id",
+        )))
+}
+
+pub async fn find_by_ids(
+    ids: Vec<String>,
     user_id: String,
-    paginator: Paginator,
     db: &DatabaseConnection,
-) -> Result<PaginatedTodo, DbErr> {
-    let mut result = PaginatedTodo::default();
-    let mut todos = Todo::find()
+) -> Result<Vec<todo::Model>, DbErr> {
+    let todos = Todo::find()
+        .filter(todo::Column::Id.is_in(ids.clone()))
+        .filter(reachable(&user_id))
+        .filter(todo::Column::DeletedAt.is_null())
+        .all(db)
+        .await?;
+
+    let mut todos: HashMap<String, todo::Model> = todos
+        .into_iter()
+        .map(|todo| (todo.id.clone(), todo))
+        .collect();
+
+    Ok(ids.iter().filter_map(|id| todos.remove(id)).collect())
+}
+
+async fn update_owned<C>(
+    model: todo::ActiveModel,
+    user_id: &str,
+    version: Option<i32>,
+    db: &C,
+) -> Result<todo::Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let id = model
+        .id
+        .clone()
+        .take()
+        .ok_or(DbErr::Custom(String::from("Todo id is required to update")))?;
+
+    let changed = todo::Column::iter()
+        .any(|column| !matches!(column, todo::Column::Id) && model.get(column).is_set());
+    // sea-orm answers an update without columns with the row found by id alone
+    if !changed {
+        let todo = find_owned(&id, user_id, db).await?;
+        return match version {
+            Some(version) if version != todo.version => Err(DbErr::RecordNotUpdated),
+            _ => Ok(todo),
+        };
+    }
+
+    let result = Todo::update(model)
         .filter(todo::Column::UserId.eq(user_id))
+        .filter(todo::Column::DeletedAt.is_null())
+        .apply_if(version, |query, version| {
+            query.filter(todo::Column::Version.eq(version))
+        })
+        .exec(db)
+        .await;
+
+    match result {
+        Err(DbErr::RecordNotUpdated) => {
+            find_owned(&id, user_id, db).await?;
+            Err(DbErr::RecordNotUpdated)
+        }
+        result => result,
+    }
+}
+
+fn scope(user_id: &str, workspace_id: Option<String>) -> Condition {
+    match workspace_id {
+        Some(workspace_id) => Condition::all().add(todo::Column::WorkspaceId.eq(workspace_id)),
+        None => Condition::all()
+            .add(database::share::accessible(user_id))
+            .add(todo::Column::WorkspaceId.is_null()),
+    }
+}
+
+fn in_workspace(workspace_id: Option<String>) -> SimpleExpr {
+    match workspace_id {
+        Some(workspace_id) => todo::Column::WorkspaceId.eq(workspace_id),
+        None => todo::Column::WorkspaceId.is_null(),
+    }
+}
+
+fn reachable(user_id: &str) -> Condition {
+    let workspaces = Query::select()
+        .column(workspace_member::Column::WorkspaceId)
+        .from(WorkspaceMember)
+        .and_where(workspace_member::Column::UserId.eq(user_id))
+        .to_owned();
+
+    Condition::any()
+        .add(scope(user_id, None))
+        .add(todo::Column::WorkspaceId.in_subquery(workspaces))
+}
+
+fn filter_condition(filter: &TodoFilter, user_id: &str) -> Condition {
+    let mut condition = Condition::all();
+    let now = time::now();
+
+    if !filter.include_archived.unwrap_or(false) {
+        condition = condition.add(todo::Column::Archived.eq(false));
+    }
+    if let Some(completed) = filter.completed {
+        condition = condition.add(todo::Column::Completed.eq(completed));
+    }
+    if let Some(created_after) = filter.created_after {
+        condition = condition.add(todo::Column::CreatedAt.gt(created_after));
+    }
+    if let Some(created_before) = filter.created_before {
+        condition = condition.add(todo::Column::CreatedAt.lt(created_before));
+    }
+    if let Some(updated_after) = filter.updated_after {
+        condition = condition.add(todo::Column::UpdatedAt.gt(updated_after));
+    }
+    if let Some(updated_before) = filter.updated_before {
+        condition = condition.add(todo::Column::UpdatedAt.lt(updated_before));
+    }
+
+    match filter.due {
+        Some(DueFilter::Overdue) => {
+            condition = condition
+                .add(todo::Column::DueAt.lt(now))
+                .add(todo::Column::Completed.eq(false));
+        }
+        Some(DueFilter::Today) => {
+            let start = time::start_of_day(now);
+            condition = condition
+                .add(todo::Column::DueAt.gte(start))
+                .add(todo::Column::DueAt.lt(start + SECONDS_IN_DAY));
+        }
+        None => {}
+    }
+    if let Some(due_before) = filter.due_before {
+        condition = condition.add(todo::Column::DueAt.lt(due_before));
+    }
+    match filter.project.as_deref() {
+        Some(INBOX) => condition = condition.add(todo::Column::ProjectId.is_null()),
+        Some(project_id) => condition = condition.add(todo::Column::ProjectId.eq(project_id)),
+        None => {}
+    }
+    match filter.assigned_to.as_deref() {
+        Some(ME) => condition = condition.add(todo::Column::AssigneeId.eq(user_id)),
+        Some(assignee_id) => condition = condition.add(todo::Column::AssigneeId.eq(assignee_id)),
+        None => {}
+    }
+    if let Some(tag) = &filter.tag {
+        condition = condition.add(
+            todo::Column::Id.in_subquery(
+                sea_query::Query::select()
+                    .column(todo_tag::Column::TodoId)
+                    .from(todo_tag::Entity)
+                    .and_where(todo_tag::Column::TagId.eq(tag))
+                    .to_owned(),
+            ),
+        );
+    }
+
+    condition
+}
+
+fn sort_order(filter: &TodoFilter) -> Order {
+    match filter.order {
+        Some(SortOrder::Asc) => Order::Asc,
+        Some(SortOrder::Desc) => Order::Desc,
+        None => match filter.sort.unwrap_or_default() {
+            TodoSort::Title | TodoSort::Position => Order::Asc,
+            _ => Order::Desc,
+        },
+    }
+}
+
+struct SortKey {
+    column: todo::Column,
+    order: Order,
+    nulls_last: bool,
+}
+
+impl SortKey {
+    fn new(column: todo::Column, order: Order) -> Self {
+        Self {
+            column,
+            order,
+            nulls_last: false,
+        }
+    }
+
+    fn ascending(&self, backwards: bool) -> bool {
+        (self.order == Order::Asc) != backwards
+    }
+}
+
+fn sort_keys(filter: &TodoFilter) -> Vec<SortKey> {
+    let order = sort_order(filter);
+
+    match filter.sort.unwrap_or_default() {
+        TodoSort::Priority => vec![
+            SortKey::new(todo::Column::Priority, order),
+            SortKey {
+                column: todo::Column::DueAt,
+                order: Order::Asc,
+                nulls_last: true,
+            },
+            SortKey::new(todo::Column::Id, Order::Asc),
+        ],
+        TodoSort::CreatedAt => vec![SortKey::new(todo::Column::Id, order)],
+        TodoSort::UpdatedAt => vec![
+            SortKey::new(todo::Column::UpdatedAt, order.clone()),
+            SortKey::new(todo::Column::Id, order),
+        ],
+        TodoSort::Title => vec![
+            SortKey::new(todo::Column::Title, order.clone()),
+            SortKey::new(todo::Column::Id, order),
+        ],
+        TodoSort::Position => vec![
+            SortKey::new(todo::Column::Position, order.clone()),
+            SortKey::new(todo::Column::Id, order),
+        ],
+    }
+}
+
+fn sort_name(filter: &TodoFilter) -> String {
+    format!(
+        "{:?}:{:?}",
+        filter.sort.unwrap_or_default(),
+        sort_order(filter)
+    )
+}
+
+fn sort(mut query: Select<Todo>, keys: &[SortKey], backwards: bool) -> Select<Todo> {
+    for key in keys {
+        let order = if key.ascending(backwards) {
+            Order::Asc
+        } else {
+            Order::Desc
+        };
+
+        query = if key.nulls_last {
+            let nulls = if backwards {
+                NullOrdering::First
+            } else {
+                NullOrdering::Last
+            };
+            query.order_by_with_nulls(key.column, order, nulls)
+        } else {
+            query.order_by(key.column, order)
+        };
+    }
+
+    query
+}
+
+fn cursor_key(todo: &todo::Model, keys: &[SortKey]) -> Vec<Json> {
+    let todo = serde_json::to_value(todo).unwrap_or_default();
+
+    keys.iter()
+        .map(|key| todo.get(key.column.as_str()).cloned().unwrap_or_default())
+        .collect()
+}
+
+fn key_value(column: todo::Column, value: &Json) -> Option<Option<Value>> {
+    match column {
+        todo::Column::Priority => serde_json::from_value::<Priority>(value.clone())
+            .ok()
+            .map(|priority| Some(priority.into())),
+        todo::Column::DueAt if value.is_null() => Some(None),
+        todo::Column::DueAt | todo::Column::UpdatedAt => {
+            value.as_i64().map(|value| Some(value.into()))
+        }
+        _ => value.as_str().map(|value| Some(value.into())),
+    }
+}
+
+fn cursor_condition(filter: &TodoFilter, cursor: &Cursor) -> Option<Condition> {
+    let keys = sort_keys(filter);
+    if cursor.sort != sort_name(filter) || cursor.key.len() != keys.len() {
+        return None;
+    }
+    let backwards = cursor.direction == Direction::Before;
+
+    // Past the cursor means equal on the leading keys and past it on the next
+    let mut condition = Condition::any();
+    let mut equal = Condition::all();
+    for (key, value) in keys.iter().zip(&cursor.key) {
+        let value = key_value(key.column, value)?;
+
+        let beyond = match value.clone() {
+            Some(value) => {
+                let beyond = Condition::any().add(if key.ascending(backwards) {
+                    key.column.gt(value)
+                } else {
+                    key.column.lt(value)
+                });
+                if key.nulls_last && !backwards {
+                    Some(beyond.add(key.column.is_null()))
+                } else {
+                    Some(beyond)
+                }
+            }
+            None if backwards => Some(Condition::all().add(key.column.is_not_null())),
+            // Only more rows without a value follow one without a value
+            None => None,
+        };
+        if let Some(beyond) = beyond {
+            condition = condition.add(equal.clone().add(beyond));
+        }
+
+        equal = equal.add(match value {
+            Some(value) => key.column.eq(value),
+            None => key.column.is_null(),
+        });
+    }
+
+    Some(condition)
+}
+
+pub fn accepts_cursor(filter: &TodoFilter, cursor: &Cursor) -> bool {
+    cursor_condition(filter, cursor).is_some()
+}
+
+async fn find_page_by_offset(
+    query: Select<Todo>,
+    filter: &TodoFilter,
+    paginator: &Paginator,
+    result: &mut PaginatedTodo,
+    db: &DatabaseConnection,
+) -> Result<Vec<todo::Model>, DbErr> {
+    let mut todos = sort(query, &sort_keys(filter), false)
         .offset(paginator.skip)
         .limit(paginator.take + 1)
         .all(db)
@@ -43,49 +493,387 @@ pub async fn find_by_user_id(
 
     if todos.len().try_into().unwrap_or(0) == paginator.take + 1 {
         result.has_next = true;
-        result.next_offset = Some(paginator.skip + paginator.take + 1);
+        result.next_offset = Some(paginator.skip + paginator.take);
         todos.pop();
     }
-    result.todos = todos;
 
-    Ok(result)
+    Ok(todos)
+}
+
+async fn find_page_by_cursor(
+    mut query: Select<Todo>,
+    filter: &TodoFilter,
+    paginator: &Paginator,
+    result: &mut PaginatedTodo,
+    db: &DatabaseConnection,
+) -> Result<Vec<todo::Model>, DbErr> {
+    let keys = sort_keys(filter);
+    let backwards = paginator
+        .cursor
+        .as_ref()
+        .is_some_and(|cursor| cursor.direction == Direction::Before);
+
+    if let Some(cursor) = &paginator.cursor {
+        query = query.filter(cursor_condition(filter, cursor).ok_or(DbErr::Custom(
+            String::from("Cursor does not belong to the ordering of the listing"),
+        ))?);
+    }
+
+    let mut todos = sort(query, &keys, backwards)
+        .limit(paginator.take + 1)
+        .all(db)
+        .await?;
+
+    let has_more = todos.len().try_into().unwrap_or(0) == paginator.take + 1;
+    if has_more {
+        todos.pop();
+    }
+    if backwards {
+        todos.reverse();
+    }
+
+    let (has_next, has_prev) = if backwards {
+        (true, has_more)
+    } else {
+        (has_more, paginator.cursor.is_some())
+    };
+
+    let sort = sort_name(filter);
+    let cursor = |todo: &todo::Model, direction: Direction| {
+        Cursor {
+            direction,
+            sort: sort.clone(),
+            key: cursor_key(todo, &keys),
+        }
+        .encode()
+    };
+
+    result.has_next = has_next && !todos.is_empty();
+    if result.has_next {
+        result.next_cursor = todos.last().map(|todo| cursor(todo, Direction::After));
+        if paginator.cursor.is_none() {
+            result.next_offset = Some(paginator.take);
+        }
+    }
+    if has_prev {
+        result.prev_cursor = todos.first().map(|todo| cursor(todo, Direction::Before));
+    }
+
+    Ok(todos)
 }
 
-#[derive(DerivePartialModel, FromQueryResult)]
-#[sea_orm(entity = "Todo")]
-struct TodoCompleted {
-    completed: bool,
+pub async fn find_by_user_id(
+    user_id: String,
+    workspace_id: Option<String>,
+    filter: TodoFilter,
+    paginator: Paginator,
+    db: &DatabaseConnection,
+) -> Result<PaginatedTodo, DbErr> {
+    let mut result = PaginatedTodo::default();
+    let query = Todo::find()
+        .filter(scope(&user_id, workspace_id))
+        .filter(todo::Column::DeletedAt.is_null())
+        .filter(filter_condition(&filter, &user_id));
+
+    // Asking for a page past the first one picks offset paging
+    let todos = if paginator.skip > 0 {
+        find_page_by_offset(query, &filter, &paginator, &mut result, db).await?
+    } else {
+        find_page_by_cursor(query, &filter, &paginator, &mut result, db).await?
+    };
+
+    let mut shared_by = database::share::find_shared_by(&user_id, &todos, db).await?;
+    let assignees = find_assignees(&todos, db).await?;
+    let comment_counts = database::todo_comment::count_by_todo_ids(
+        todos.iter().map(|todo| todo.id.clone()).collect(),
+        db,
+    )
+    .await?;
+    let mut items: Vec<TodoItem> = todos
+        .into_iter()
+        .map(|todo| TodoItem {
+            shared_by: shared_by.remove(&todo.id),
+            assignee: todo
+                .assignee_id
+                .as_ref()
+                .and_then(|assignee_id| assignees.get(assignee_id))
+                .cloned(),
+            comment_count: comment_counts.get(&todo.id).copied().unwrap_or_default(),
+            ..TodoItem::from(todo)
+        })
+        .collect();
+    if filter.with_subtasks.unwrap_or(false) {
+        let counts = database::subtask::count_by_todo_ids(
+            items.iter().map(|item| item.todo.id.clone()).collect(),
+            db,
+        )
+        .await?;
+
+        for item in items.iter_mut() {
+            item.subtasks = Some(counts.get(&item.todo.id).copied().unwrap_or_default());
+        }
+    }
+    result.todos = items;
+
+    Ok(result)
 }
 
-pub async fn mark(id: String, user_id: String, db: &DatabaseConnection) -> Result<(), DbErr> {
-    let completed = Todo::find_by_id(id.clone())
-        .into_partial_model::<TodoCompleted>()
-        .one(db)
+pub async fn find_assignees(
+    todos: &[todo::Model],
+    db: &DatabaseConnection,
+) -> Result<HashMap<String, Assignee>, DbErr> {
+    let ids: Vec<String> = todos
+        .iter()
+        .filter_map(|todo| todo.assignee_id.clone())
+        .collect();
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    Ok(database::user::find_by_ids(ids, db)
         .await?
-        .ok_or(DbErr::RecordNotFound(String::from(
-            "Todo not found for the given id",
-        )))?
-        .completed;
+        .into_iter()
+        .map(|user| (user.id.clone(), Assignee::from(user)))
+        .collect())
+}
 
-    let result = todo::ActiveModel {
-        id: Set(id),
-        user_id: Set(user_id),
-        completed: Set(!completed),
+const TS_QUERY: &str = "websearch_to_tsquery('english', $1)";
+const TS_HEADLINE_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>, MaxFragments=2";
+
+fn headline(column: &str) -> String {
+    format!(
+        r#"ts_headline('english', replace(replace(replace("todo_"."{column}", '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), {TS_QUERY}, '{TS_HEADLINE_OPTIONS}')"#
+    )
+}
+
+pub async fn search(
+    user_id: String,
+    workspace_id: Option<String>,
+    search: TodoSearchQuery,
+    paginator: Paginator,
+    db: &DatabaseConnection,
+) -> Result<TodoSearchResult, DbErr> {
+    let statement = Todo::find()
+        .expr_as(
+            Expr::cust_with_values(
+                format!(r#"ts_rank("todo_"."search", {TS_QUERY})"#),
+                [search.q.clone()],
+            ),
+            "rank",
+        )
+        .expr_as(
+            Expr::cust_with_values(headline("title"), [search.q.clone()]),
+            "title_highlight",
+        )
+        .expr_as(
+            Expr::cust_with_values(headline("content"), [search.q.clone()]),
+            "content_highlight",
+        )
+        .filter(scope(&user_id, workspace_id))
+        .filter(todo::Column::DeletedAt.is_null())
+        .apply_if(
+            (!search.include_archived.unwrap_or(false)).then_some(()),
+            |query, _| query.filter(todo::Column::Archived.eq(false)),
+        )
+        .filter(Expr::cust_with_values(
+            format!(r#""todo_"."search" @@ {TS_QUERY}"#),
+            [search.q],
+        ))
+        .order_by_desc(Expr::col(Alias::new("rank")))
+        .order_by_desc(todo::Column::Id)
+        .offset(paginator.skip)
+        .limit(paginator.take + 1)
+        .build(db.get_database_backend());
+
+    let mut rows = db.query_all(statement).await?;
+
+    let mut result = TodoSearchResult::default();
+    if rows.len().try_into().unwrap_or(0) == paginator.take + 1 {
+        result.has_next = true;
+        result.next_offset = Some(paginator.skip + paginator.take);
+        rows.pop();
+    }
+
+    result.results = rows
+        .iter()
+        .map(|row| {
+            Ok(TodoSearchHit {
+                todo: todo::Model::from_query_result(row, "")?,
+                rank: row.try_get("", "rank")?,
+                highlight: TodoHighlight {
+                    title: row.try_get("", "title_highlight")?,
+                    content: row.try_get("", "content_highlight")?,
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, DbErr>>()?;
+
+    Ok(result)
+}
+
+pub async fn mark<C>(
+    id: String,
+    user_id: String,
+    actor_id: &str,
+    session_id: Option<String>,
+    cascade: bool,
+    version: Option<i32>,
+    db: &C,
+) -> Result<TodoChange, DbErr>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let txn = db.begin().await?;
+
+    let todo = find_owned(&id, &user_id, &txn).await?;
+    if version.is_some_and(|version| version != todo.version) {
+        return Err(DbErr::RecordNotUpdated);
+    }
+    let completed = !todo.completed;
+
+    let mut result = todo::ActiveModel {
+        id: Set(id.clone()),
+        completed: Set(completed),
         ..Default::default()
     };
 
-    result.save(db).await?;
-    Ok(())
+    let mut occurrence = None;
+    if completed {
+        if let Some(recurrence) = recurrence_from_json(todo.recurrence.clone())? {
+            if let Some(due_at) = recurrence.next_due(todo.due_at, time::now()) {
+                occurrence = Some(
+                    create_occurrence(&todo, due_at, actor_id, session_id.clone(), &txn).await?,
+                );
+                // the series continues on the new occurrence
+                result.recurrence = Set(None);
+            }
+        }
+    }
+
+    let updated = update_owned(result, &user_id, version, &txn).await?;
+    if cascade && completed {
+        database::subtask::complete_all(&id, &txn).await?;
+    }
+    database::todo_event::record(
+        TodoAction::Mark,
+        actor_id,
+        Some(&todo),
+        Some(&updated),
+        session_id,
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+    Ok(TodoChange {
+        before: todo,
+        after: updated,
+        occurrence,
+    })
+}
+
+async fn create_occurrence<C>(
+    todo: &todo::Model,
+    due_at: i64,
+    actor_id: &str,
+    session_id: Option<String>,
+    db: &C,
+) -> Result<todo::Model, DbErr>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let next = insert_last(
+        todo::ActiveModel {
+            user_id: Set(todo.user_id.clone()),
+            title: Set(todo.title.clone()),
+            content: Set(todo.content.clone()),
+            completed: Set(false),
+            due_at: Set(Some(due_at)),
+            priority: Set(todo.priority),
+            project_id: Set(todo.project_id.clone()),
+            recurrence: Set(todo.recurrence.clone()),
+            workspace_id: Set(todo.workspace_id.clone()),
+            ..Default::default()
+        },
+        db,
+    )
+    .await?;
+    database::todo_event::record(
+        TodoAction::Create,
+        actor_id,
+        None,
+        Some(&next),
+        session_id,
+        db,
+    )
+    .await?;
+
+    let tags = TodoTag::find()
+        .filter(todo_tag::Column::TodoId.eq(&todo.id))
+        .all(db)
+        .await?;
+    if !tags.is_empty() {
+        TodoTag::insert_many(tags.into_iter().map(|tag| todo_tag::ActiveModel {
+            todo_id: Set(next.id.clone()),
+            tag_id: Set(tag.tag_id),
+        }))
+        .exec(db)
+        .await?;
+    }
+
+    let subtasks = Subtask::find()
+        .filter(subtask::Column::TodoId.eq(&todo.id))
+        .all(db)
+        .await?;
+    if !subtasks.is_empty() {
+        Subtask::insert_many(subtasks.into_iter().map(|subtask| subtask::ActiveModel {
+            todo_id: Set(next.id.clone()),
+            title: Set(subtask.title),
+            completed: Set(false),
+            position: Set(subtask.position),
+            ..Default::default()
+        }))
+        .exec(db)
+        .await?;
+    }
+
+    Ok(next)
+}
+
+fn recurrence_to_json(recurrence: Option<Recurrence>) -> Result<Option<Json>, DbErr> {
+    recurrence
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|err| DbErr::Custom(format!("Failed to serialize the recurrence: {}", err)))
 }
 
-pub async fn update(
+fn recurrence_from_json(recurrence: Option<Json>) -> Result<Option<Recurrence>, DbErr> {
+    recurrence
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|err| DbErr::Custom(format!("Failed to parse the recurrence: {}", err)))
+}
+
+pub async fn update<C>(
     user_id: String,
+    actor_id: &str,
+    session_id: Option<String>,
     data: UpdateTodoReq,
-    db: &DatabaseConnection,
-) -> Result<todo::Model, DbErr> {
+    version: Option<i32>,
+    db: &C,
+) -> Result<TodoChange, DbErr>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let txn = db.begin().await?;
+
+    let todo = find_owned(&data.id, &user_id, &txn).await?;
+    if let Some(Some(project_id)) = &data.project_id {
+        database::project::ensure_owned(project_id, &user_id, &txn).await?;
+    }
+
     let mut result = todo::ActiveModel {
         id: Set(data.id),
-        user_id: Set(user_id),
         ..Default::default()
     };
 
@@ -98,18 +886,444 @@ pub async fn update(
     if let Some(completed) = data.completed {
         result.completed = Set(completed);
     }
+    if let Some(due_at) = data.due_at {
+        result.due_at = Set(due_at);
+    }
+    if let Some(priority) = data.priority {
+        result.priority = Set(priority);
+    }
+    if let Some(project_id) = data.project_id {
+        result.project_id = Set(project_id);
+    }
+    if let Some(recurrence) = data.recurrence {
+        result.recurrence = Set(recurrence_to_json(recurrence)?);
+    }
+
+    let updated = update_owned(result, &user_id, version, &txn).await?;
+    database::todo_event::record(
+        TodoAction::Update,
+        actor_id,
+        Some(&todo),
+        Some(&updated),
+        session_id,
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+    Ok(TodoChange {
+        before: todo,
+        after: updated,
+        occurrence: None,
+    })
+}
+
+pub async fn reposition(
+    id: String,
+    user_id: String,
+    after_id: Option<String>,
+    before_id: Option<String>,
+    db: &DatabaseConnection,
+) -> Result<Option<todo::Model>, DbErr> {
+    let txn = db.begin().await?;
+
+    let todo = find_owned(&id, &user_id, &txn).await?;
+
+    let lower = match &after_id {
+        Some(after_id) => Some(find_sibling(after_id, &todo, &txn).await?.position),
+        None => None,
+    };
+    let upper = match &before_id {
+        Some(before_id) => Some(find_sibling(before_id, &todo, &txn).await?.position),
+        None => None,
+    };
+    if let (Some(lower), Some(upper)) = (&lower, &upper) {
+        if lower >= upper {
+            return Ok(None);
+        }
+    }
+
+    let mut attempt = 1;
+    let todo = loop {
+        let savepoint = txn.begin().await?;
+
+        match place(&todo, lower.as_deref(), upper.as_deref(), &savepoint).await {
+            Err(err) if attempt < POSITION_ATTEMPTS && is_position_conflict(&err) => {
+                savepoint.rollback().await?;
+                attempt += 1;
+            }
+            result => {
+                let todo = result?;
+                savepoint.commit().await?;
+                break todo;
+            }
+        }
+    };
+
+    txn.commit().await?;
+    Ok(todo)
+}
+
+async fn find_sibling<C>(id: &str, todo: &todo::Model, db: &C) -> Result<todo::Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Todo::find_by_id(id)
+        .filter(ordered_with(&todo.user_id, todo.workspace_id.as_deref()))
+        .filter(todo::Column::DeletedAt.is_null())
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Todo not found for the given id",
+        )))
+}
+
+async fn place<C>(
+    todo: &todo::Model,
+    lower: Option<&str>,
+    upper: Option<&str>,
+    db: &C,
+) -> Result<Option<todo::Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let siblings = || {
+        Todo::find()
+            .select_only()
+            .column(todo::Column::Position)
+            .filter(ordered_with(&todo.user_id, todo.workspace_id.as_deref()))
+            .filter(todo::Column::Id.ne(&todo.id))
+    };
+
+    let (lower, upper) = match (lower, upper) {
+        (Some(lower), _) => {
+            let upper = siblings()
+                .filter(todo::Column::Position.gt(lower))
+                .order_by_asc(todo::Column::Position)
+                .into_tuple::<String>()
+                .one(db)
+                .await?;
+            (Some(lower.to_owned()), upper)
+        }
+        (None, Some(upper)) => {
+            let lower = siblings()
+                .filter(todo::Column::Position.lt(upper))
+                .order_by_desc(todo::Column::Position)
+                .into_tuple::<String>()
+                .one(db)
+                .await?;
+            (lower, Some(upper.to_owned()))
+        }
+        (None, None) => (None, None),
+    };
+
+    let Some(position) = key_between(lower.as_deref(), upper.as_deref()) else {
+        return Ok(None);
+    };
+
+    update_owned(
+        todo::ActiveModel {
+            id: Set(todo.id.clone()),
+            position: Set(position),
+            ..Default::default()
+        },
+        &todo.user_id,
+        None,
+        db,
+    )
+    .await
+    .map(Some)
+}
+
+pub async fn delete<C>(
+    id: String,
+    user_id: String,
+    session_id: Option<String>,
+    version: Option<i32>,
+    db: &C,
+) -> Result<TodoChange, DbErr>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    let txn = db.begin().await?;
+
+    let todo = find_owned(&id, &user_id, &txn).await?;
+    let deleted = update_owned(
+        todo::ActiveModel {
+            id: Set(id),
+            deleted_at: Set(Some(time::now())),
+            ..Default::default()
+        },
+        &user_id,
+        version,
+        &txn,
+    )
+    .await?;
+    database::todo_event::record(
+        TodoAction::Delete,
+        &user_id,
+        Some(&todo),
+        Some(&deleted),
+        session_id,
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+    Ok(TodoChange {
+        before: todo,
+        after: deleted,
+        occurrence: None,
+    })
+}
+
+pub async fn assign(
+    id: String,
+    user_id: String,
+    assignee_id: Option<String>,
+    session_id: Option<String>,
+    db: &DatabaseConnection,
+) -> Result<TodoChange, DbErr> {
+    let txn = db.begin().await?;
+
+    let todo = find_owned(&id, &user_id, &txn).await?;
+    let assigned = update_owned(
+        todo::ActiveModel {
+            id: Set(id),
+            assignee_id: Set(assignee_id),
+            ..Default::default()
+        },
+        &user_id,
+        None,
+        &txn,
+    )
+    .await?;
+    database::todo_event::record(
+        TodoAction::Update,
+        &user_id,
+        Some(&todo),
+        Some(&assigned),
+        session_id,
+        &txn,
+    )
+    .await?;
+
+    txn.commit().await?;
+    Ok(TodoChange {
+        before: todo,
+        after: assigned,
+        occurrence: None,
+    })
+}
+
+pub async fn archive(
+    id: String,
+    user_id: String,
+    archived: bool,
+    db: &DatabaseConnection,
+) -> Result<todo::Model, DbErr> {
+    update_owned(
+        todo::ActiveModel {
+            id: Set(id),
+            archived: Set(archived),
+            ..Default::default()
+        },
+        &user_id,
+        None,
+        db,
+    )
+    .await
+}
+
+pub async fn archive_completed(
+    user_id: String,
+    workspace_id: Option<String>,
+    db: &DatabaseConnection,
+) -> Result<u64, DbErr> {
+    Ok(Todo::update_many()
+        .col_expr(todo::Column::Archived, Expr::value(true))
+        .filter(todo::Column::UserId.eq(user_id))
+        .filter(in_workspace(workspace_id))
+        .filter(todo::Column::Completed.eq(true))
+        .filter(todo::Column::Archived.eq(false))
+        .filter(todo::Column::DeletedAt.is_null())
+        .exec(db)
+        .await?
+        .rows_affected)
+}
+
+pub async fn find_deleted_by_user_id(
+    user_id: String,
+    workspace_id: Option<String>,
+    paginator: Paginator,
+    db: &DatabaseConnection,
+) -> Result<PaginatedTodo, DbErr> {
+    let mut result = PaginatedTodo::default();
+    let mut todos = Todo::find()
+        .filter(todo::Column::UserId.eq(user_id))
+        .filter(in_workspace(workspace_id))
+        .filter(todo::Column::DeletedAt.is_not_null())
+        .order_by_desc(todo::Column::DeletedAt)
+        .order_by_desc(todo::Column::Id)
+        .offset(paginator.skip)
+        .limit(paginator.take + 1)
+        .all(db)
+        .await?;
+
+    if todos.len().try_into().unwrap_or(0) == paginator.take + 1 {
+        result.has_next = true;
+        result.next_offset = Some(paginator.skip + paginator.take);
+        todos.pop();
+    }
+    let comment_counts = database::todo_comment::count_by_todo_ids(
+        todos.iter().map(|todo| todo.id.clone()).collect(),
+        db,
+    )
+    .await?;
+    result.todos = todos
+        .into_iter()
+        .map(|todo| TodoItem {
+            comment_count: comment_counts.get(&todo.id).copied().unwrap_or_default(),
+            ..TodoItem::from(todo)
+        })
+        .collect();
 
-    result.save(db).await?.try_into_model()
+    Ok(result)
 }
 
-pub async fn delete(id: String, user_id: String, db: &DatabaseConnection) -> Result<(), DbErr> {
-    todo::ActiveModel {
+pub async fn restore(
+    id: String,
+    user_id: String,
+    session_id: Option<String>,
+    db: &DatabaseConnection,
+) -> Result<todo::Model, DbErr> {
+    let txn = db.begin().await?;
+
+    let todo = Todo::find_by_id(&id)
+        .filter(todo::Column::UserId.eq(&user_id))
+        .filter(todo::Column::DeletedAt.is_not_null())
+        .one(&txn)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Deleted todo not found for the given id",
+        )))?;
+
+    let restored = todo::ActiveModel {
         id: Set(id),
-        user_id: Set(user_id),
+        deleted_at: Set(None),
         ..Default::default()
     }
-    .delete(db)
+    .update(&txn)
+    .await?;
+    database::todo_event::record(
+        TodoAction::Restore,
+        &user_id,
+        Some(&todo),
+        Some(&restored),
+        session_id,
+        &txn,
+    )
     .await?;
 
-    Ok(())
+    txn.commit().await?;
+    Ok(restored)
+}
+
+pub async fn purge_deleted(
+    deleted_before: i64,
+    db: &DatabaseConnection,
+) -> Result<(u64, Vec<String>), DbErr> {
+    let txn = db.begin().await?;
+
+    let ids: Vec<String> = Todo::find()
+        .select_only()
+        .column(todo::Column::Id)
+        .filter(todo::Column::DeletedAt.lt(deleted_before))
+        .lock_exclusive()
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    if ids.is_empty() {
+        return Ok((0, Vec::new()));
+    }
+
+    let storage_keys = database::todo_attachment::find_keys_by_todo_ids(ids.clone(), &txn).await?;
+    let purged = Todo::delete_many()
+        .filter(todo::Column::Id.is_in(ids))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+
+    txn.commit().await?;
+    Ok((purged, storage_keys))
+}
+
+pub async fn undo(
+    actor_id: &str,
+    session_id: Option<String>,
+    record: UndoRecord,
+    db: &DatabaseConnection,
+) -> Result<Vec<todo::Model>, DbErr> {
+    let txn = db.begin().await?;
+
+    let mut current = Vec::with_capacity(record.snapshots.len());
+    for snapshot in record.snapshots.iter().chain(record.created.iter()) {
+        current.push(
+            Todo::find_by_id(&snapshot.todo.id)
+                .filter(todo::Column::UserId.eq(&snapshot.todo.user_id))
+                .filter(todo::Column::Version.eq(snapshot.version))
+                .one(&txn)
+                .await?
+                .ok_or(DbErr::RecordNotUpdated)?,
+        );
+    }
+
+    let mut reverted = Vec::with_capacity(record.snapshots.len());
+    for (snapshot, current) in record.snapshots.into_iter().zip(current) {
+        let before = snapshot.todo;
+        let todo = Todo::update(todo::ActiveModel {
+            id: Set(before.id.clone()),
+            title: Set(before.title.clone()),
+            content: Set(before.content.clone()),
+            completed: Set(before.completed),
+            due_at: Set(before.due_at),
+            priority: Set(before.priority),
+            project_id: Set(before.project_id.clone()),
+            recurrence: Set(before.recurrence.clone()),
+            deleted_at: Set(before.deleted_at),
+            archived: Set(before.archived),
+            ..Default::default()
+        })
+        .filter(todo::Column::UserId.eq(&before.user_id))
+        .filter(todo::Column::Version.eq(snapshot.version))
+        .exec(&txn)
+        .await?;
+
+        database::todo_event::record(
+            TodoAction::Undo,
+            actor_id,
+            Some(&current),
+            Some(&todo),
+            session_id.clone(),
+            &txn,
+        )
+        .await?;
+        reverted.push(todo);
+    }
+
+    for snapshot in record.created {
+        let result = Todo::delete_many()
+            .filter(todo::Column::Id.eq(snapshot.todo.id))
+            .filter(todo::Column::UserId.eq(snapshot.todo.user_id))
+            .filter(todo::Column::Version.eq(snapshot.version))
+            .exec(&txn)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(DbErr::RecordNotUpdated);
+        }
+    }
+
+    txn.commit().await?;
+    Ok(reverted)
 }
@@ -0,0 +1,66 @@
+use crate::{
+    entity::{prelude::TodoAttachment, todo_attachment},
+    model::todo_attachment::StoredFile,
+};
+use sea_orm::*;
+
+pub async fn add(
+    todo_id: String,
+    user_id: String,
+    file: StoredFile,
+    db: &DatabaseConnection,
+) -> Result<todo_attachment::Model, DbErr> {
+    TodoAttachment::insert(todo_attachment::ActiveModel {
+        todo_id: Set(todo_id),
+        user_id: Set(user_id),
+        file_name: Set(file.file_name),
+        content_type: Set(file.content_type),
+        size: Set(file.size),
+        storage_key: Set(file.storage_key),
+        ..Default::default()
+    })
+    .exec_with_returning(db)
+    .await
+}
+
+pub async fn find_by_id(
+    id: String,
+    db: &DatabaseConnection,
+) -> Result<todo_attachment::Model, DbErr> {
+    TodoAttachment::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Attachment not found for the given id",
+        )))
+}
+
+pub async fn find_by_todo_id(
+    todo_id: String,
+    db: &DatabaseConnection,
+) -> Result<Vec<todo_attachment::Model>, DbErr> {
+    TodoAttachment::find()
+        .filter(todo_attachment::Column::TodoId.eq(todo_id))
+        .order_by_asc(todo_attachment::Column::Id)
+        .all(db)
+        .await
+}
+
+pub async fn delete(id: String, db: &DatabaseConnection) -> Result<(), DbErr> {
+    TodoAttachment::delete_by_id(id).exec(db).await?;
+
+    Ok(())
+}
+
+pub async fn find_keys_by_todo_ids<C>(todo_ids: Vec<String>, db: &C) -> Result<Vec<String>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    TodoAttachment::find()
+        .select_only()
+        .column(todo_attachment::Column::StorageKey)
+        .filter(todo_attachment::Column::TodoId.is_in(todo_ids))
+        .into_tuple()
+        .all(db)
+        .await
+}
@@ -0,0 +1,99 @@
+use crate::{
+    entity::{prelude::TodoComment, todo_comment},
+    model::todo_comment::{AddCommentReq, PaginatedTodoComment},
+    utils::{paginate::Paginator, time},
+};
+use sea_orm::*;
+use std::collections::HashMap;
+
+pub async fn add(
+    user_id: String,
+    data: AddCommentReq,
+    db: &DatabaseConnection,
+) -> Result<todo_comment::Model, DbErr> {
+    TodoComment::insert(todo_comment::ActiveModel {
+        todo_id: Set(data.todo_id),
+        user_id: Set(user_id),
+        body: Set(data.body),
+        ..Default::default()
+    })
+    .exec_with_returning(db)
+    .await
+}
+
+pub async fn find_by_id(id: String, db: &DatabaseConnection) -> Result<todo_comment::Model, DbErr> {
+    TodoComment::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Comment not found for the given id",
+        )))
+}
+
+pub async fn find_by_todo_id(
+    todo_id: String,
+    paginator: Paginator,
+    db: &DatabaseConnection,
+) -> Result<PaginatedTodoComment, DbErr> {
+    let mut result = PaginatedTodoComment::default();
+    let mut comments = TodoComment::find()
+        .filter(todo_comment::Column::TodoId.eq(todo_id))
+        .order_by_asc(todo_comment::Column::Id)
+        .offset(paginator.skip)
+        .limit(paginator.take + 1)
+        .all(db)
+        .await?;
+
+    if comments.len().try_into().unwrap_or(0) == paginator.take + 1 {
+        result.has_next = true;
+        result.next_offset = Some(paginator.skip + paginator.take);
+        comments.pop();
+    }
+    result.comments = comments;
+
+    Ok(result)
+}
+
+pub async fn edit(
+    id: String,
+    user_id: String,
+    body: String,
+    db: &DatabaseConnection,
+) -> Result<todo_comment::Model, DbErr> {
+    TodoComment::update(todo_comment::ActiveModel {
+        id: Set(id),
+        body: Set(body),
+        edited_at: Set(Some(time::now())),
+        ..Default::default()
+    })
+    .filter(todo_comment::Column::UserId.eq(user_id))
+    .exec(db)
+    .await
+}
+
+pub async fn delete(id: String, db: &DatabaseConnection) -> Result<(), DbErr> {
+    TodoComment::delete_by_id(id).exec(db).await?;
+
+    Ok(())
+}
+
+pub async fn count_by_todo_ids(
+    todo_ids: Vec<String>,
+    db: &DatabaseConnection,
+) -> Result<HashMap<String, i64>, DbErr> {
+    if todo_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows: Vec<(String, i64)> = TodoComment::find()
+        .select_only()
+        .column(todo_comment::Column::TodoId)
+        .column_as(todo_comment::Column::Id.count(), "count")
+        .filter(todo_comment::Column::TodoId.is_in(todo_ids))
+        .group_by(todo_comment::Column::TodoId)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    Ok(rows.into_iter().collect())
+}
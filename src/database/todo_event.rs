@@ -0,0 +1,114 @@
+use crate::{
+    entity::{
+        prelude::{Todo, TodoEvent},
+        sea_orm_active_enums::TodoAction,
+        todo, todo_event,
+    },
+    model::todo_event::PaginatedTodoEvent,
+    utils::paginate::Paginator,
+};
+use sea_orm::{prelude::Json, *};
+use serde_json::Map;
+
+const IGNORED_FIELDS: [&str; 2] = ["updated_at", "version"];
+
+pub async fn record<C>(
+    action: TodoAction,
+    actor_id: &str,
+    before: Option<&todo::Model>,
+    after: Option<&todo::Model>,
+    session_id: Option<String>,
+    db: &C,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let Some(todo) = after.or(before) else {
+        return Ok(());
+    };
+
+    let (old_value, new_value) = match (before, after) {
+        (Some(before), Some(after)) => {
+            let (old_value, new_value) = diff(to_json(before)?, to_json(after)?);
+            (Some(old_value), Some(new_value))
+        }
+        (before, after) => (
+            before.map(to_json).transpose()?,
+            after.map(to_json).transpose()?,
+        ),
+    };
+
+    TodoEvent::insert(todo_event::ActiveModel {
+        todo_id: Set(todo.id.clone()),
+        user_id: Set(actor_id.to_owned()),
+        session_id: Set(session_id),
+        action: Set(action),
+        old_value: Set(old_value),
+        new_value: Set(new_value),
+        ..Default::default()
+    })
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
+
+fn to_json(todo: &todo::Model) -> Result<Json, DbErr> {
+    serde_json::to_value(todo)
+        .map_err(|err| DbErr::Custom(format!("Failed to serialize the todo: {}", err)))
+}
+
+fn diff(before: Json, after: Json) -> (Json, Json) {
+    let (Json::Object(before), Json::Object(after)) = (before, after) else {
+        return (Json::Null, Json::Null);
+    };
+
+    let mut old_value = Map::new();
+    let mut new_value = Map::new();
+    for (field, value) in after {
+        if IGNORED_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+
+        let previous = before.get(&field).cloned().unwrap_or_default();
+        if previous != value {
+            old_value.insert(field.clone(), previous);
+            new_value.insert(field, value);
+        }
+    }
+
+    (Json::Object(old_value), Json::Object(new_value))
+}
+
+pub async fn find_by_todo_id(
+    todo_id: String,
+    user_id: String,
+    paginator: Paginator,
+    db: &DatabaseConnection,
+) -> Result<PaginatedTodoEvent, DbErr> {
+    Todo::find_by_id(&todo_id)
+        .filter(todo::Column::UserId.eq(&user_id))
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Todo not found for the given id",
+        )))?;
+
+    let mut result = PaginatedTodoEvent::default();
+    let mut events = TodoEvent::find()
+        .filter(todo_event::Column::TodoId.eq(todo_id))
+        .order_by_desc(todo_event::Column::Id)
+        .offset(paginator.skip)
+        .limit(paginator.take + 1)
+        .all(db)
+        .await?;
+
+    if events.len().try_into().unwrap_or(0) == paginator.take + 1 {
+        result.has_next = true;
+        result.next_offset = Some(paginator.skip + paginator.take);
+        events.pop();
+    }
+    result.events = events;
+
+    Ok(result)
+}
@@ -0,0 +1,63 @@
+use crate::{config::ENV, model::undo::UndoRecord};
+use redis::{Client as RedisClient, ErrorKind, RedisError};
+
+fn get_key(user_id: &str, token: &str) -> String {
+    format!("undo_token:{}:{}", user_id, token)
+}
+
+pub async fn save(
+    user_id: &str,
+    record: &UndoRecord,
+    rd: &RedisClient,
+) -> Result<String, RedisError> {
+    let token = ulid::Ulid::new().to_string();
+    let value = serde_json::to_string(record).map_err(|err| {
+        RedisError::from((
+            ErrorKind::TypeError,
+            "Failed to serialize the undo record",
+            err.to_string(),
+        ))
+    })?;
+
+    let mut conn = rd.get_multiplexed_async_connection().await?;
+    redis::cmd("SET")
+        .arg(get_key(user_id, &token))
+        .arg(value)
+        .arg("EX")
+        .arg(ENV.todo_undo_ttl)
+        .query_async::<()>(&mut conn)
+        .await?;
+
+    Ok(token)
+}
+
+pub async fn find(
+    user_id: &str,
+    token: &str,
+    rd: &RedisClient,
+) -> Result<Option<UndoRecord>, RedisError> {
+    let mut conn = rd.get_multiplexed_async_connection().await?;
+    let value: Option<String> = redis::cmd("GET")
+        .arg(get_key(user_id, token))
+        .query_async(&mut conn)
+        .await?;
+
+    value
+        .map(|value| serde_json::from_str(&value))
+        .transpose()
+        .map_err(|err| {
+            RedisError::from((
+                ErrorKind::TypeError,
+                "Failed to parse the undo record",
+                err.to_string(),
+            ))
+        })
+}
+
+pub async fn remove(user_id: &str, token: &str, rd: &RedisClient) -> Result<(), RedisError> {
+    let mut conn = rd.get_multiplexed_async_connection().await?;
+    redis::cmd("DEL")
+        .arg(get_key(user_id, token))
+        .query_async::<()>(&mut conn)
+        .await
+}
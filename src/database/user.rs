@@ -20,6 +20,16 @@ pub async fn find_by_id(id: String, db: &DatabaseConnection) -> Result<Option<us
     User::find_by_id(id).one(db).await
 }
 
+pub async fn find_by_ids(
+    ids: Vec<String>,
+    db: &DatabaseConnection,
+) -> Result<Vec<user::Model>, DbErr> {
+    User::find()
+        .filter(user::Column::Id.is_in(ids))
+        .all(db)
+        .await
+}
+
 pub async fn find_by_email(
     email: &String,
     db: &DatabaseConnection,
@@ -0,0 +1,275 @@
+use crate::{
+    entity::{
+        prelude::{Todo, User, Workspace, WorkspaceInvitation, WorkspaceMember},
+        sea_orm_active_enums::WorkspaceRole,
+        todo, workspace, workspace_invitation, workspace_member,
+    },
+    model::workspace::{
+        CreateWorkspaceReq, WorkspaceInvitationItem, WorkspaceItem, WorkspaceMemberItem,
+    },
+};
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    *,
+};
+
+pub async fn find_membership<C>(
+    workspace_id: &str,
+    user_id: &str,
+    db: &C,
+) -> Result<workspace_member::Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    WorkspaceMember::find_by_id((workspace_id.to_owned(), user_id.to_owned()))
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Workspace not found for the given id",
+        )))
+}
+
+pub async fn create(
+    user_id: String,
+    data: CreateWorkspaceReq,
+    db: &DatabaseConnection,
+) -> Result<workspace::Model, DbErr> {
+    let txn = db.begin().await?;
+
+    let workspace = Workspace::insert(workspace::ActiveModel {
+        name: Set(data.name),
+        ..Default::default()
+    })
+    .exec_with_returning(&txn)
+    .await?;
+
+    WorkspaceMember::insert(workspace_member::ActiveModel {
+        workspace_id: Set(workspace.id.clone()),
+        user_id: Set(user_id),
+        role: Set(WorkspaceRole::Owner),
+        ..Default::default()
+    })
+    .exec_without_returning(&txn)
+    .await?;
+
+    txn.commit().await?;
+    Ok(workspace)
+}
+
+pub async fn find_by_user_id(
+    user_id: String,
+    db: &DatabaseConnection,
+) -> Result<Vec<WorkspaceItem>, DbErr> {
+    let memberships = WorkspaceMember::find()
+        .filter(workspace_member::Column::UserId.eq(user_id))
+        .find_also_related(Workspace)
+        .order_by_asc(workspace::Column::Name)
+        .all(db)
+        .await?;
+
+    Ok(memberships
+        .into_iter()
+        .filter_map(|(member, workspace)| {
+            workspace.map(|workspace| WorkspaceItem {
+                workspace,
+                role: member.role,
+            })
+        })
+        .collect())
+}
+
+pub async fn rename(
+    id: String,
+    name: String,
+    db: &DatabaseConnection,
+) -> Result<workspace::Model, DbErr> {
+    Workspace::update(workspace::ActiveModel {
+        id: Set(id),
+        name: Set(name),
+        ..Default::default()
+    })
+    .exec(db)
+    .await
+}
+
+pub async fn delete(id: String, db: &DatabaseConnection) -> Result<(), DbErr> {
+    Workspace::delete_by_id(id).exec(db).await?;
+
+    Ok(())
+}
+
+pub async fn find_members(
+    workspace_id: String,
+    db: &DatabaseConnection,
+) -> Result<Vec<WorkspaceMemberItem>, DbErr> {
+    let members = WorkspaceMember::find()
+        .filter(workspace_member::Column::WorkspaceId.eq(workspace_id))
+        .find_also_related(User)
+        .order_by_asc(workspace_member::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    Ok(members
+        .into_iter()
+        .filter_map(|(member, user)| {
+            user.map(|user| WorkspaceMemberItem {
+                member,
+                name: user.name,
+                email: user.email,
+            })
+        })
+        .collect())
+}
+
+pub async fn invite(
+    workspace_id: String,
+    invited_by: String,
+    user_id: String,
+    role: WorkspaceRole,
+    db: &DatabaseConnection,
+) -> Result<workspace_invitation::Model, DbErr> {
+    WorkspaceInvitation::insert(workspace_invitation::ActiveModel {
+        workspace_id: Set(workspace_id),
+        user_id: Set(user_id),
+        invited_by: Set(invited_by),
+        role: Set(role),
+        ..Default::default()
+    })
+    .on_conflict(
+        OnConflict::columns([
+            workspace_invitation::Column::UserId,
+            workspace_invitation::Column::WorkspaceId,
+        ])
+        .update_columns([
+            workspace_invitation::Column::InvitedBy,
+            workspace_invitation::Column::Role,
+        ])
+        .to_owned(),
+    )
+    .exec_with_returning(db)
+    .await
+}
+
+pub async fn find_invitations(
+    user_id: String,
+    db: &DatabaseConnection,
+) -> Result<Vec<WorkspaceInvitationItem>, DbErr> {
+    let invitations = WorkspaceInvitation::find()
+        .filter(workspace_invitation::Column::UserId.eq(user_id))
+        .find_also_related(Workspace)
+        .order_by_desc(workspace_invitation::Column::Id)
+        .all(db)
+        .await?;
+
+    Ok(invitations
+        .into_iter()
+        .filter_map(|(invitation, workspace)| {
+            workspace.map(|workspace| WorkspaceInvitationItem {
+                invitation,
+                workspace_name: workspace.name,
+            })
+        })
+        .collect())
+}
+
+pub async fn respond(
+    id: String,
+    user_id: String,
+    accept: bool,
+    db: &DatabaseConnection,
+) -> Result<Option<workspace_member::Model>, DbErr> {
+    let txn = db.begin().await?;
+
+    let invitation = WorkspaceInvitation::find_by_id(id)
+        .filter(workspace_invitation::Column::UserId.eq(&user_id))
+        .one(&txn)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Invitation not found for the given id",
+        )))?;
+    WorkspaceInvitation::delete_by_id(invitation.id)
+        .exec(&txn)
+        .await?;
+
+    let member = if accept {
+        WorkspaceMember::insert(workspace_member::ActiveModel {
+            workspace_id: Set(invitation.workspace_id.clone()),
+            user_id: Set(user_id.clone()),
+            role: Set(invitation.role),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::columns([
+                workspace_member::Column::WorkspaceId,
+                workspace_member::Column::UserId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(&txn)
+        .await?;
+
+        Some(find_membership(&invitation.workspace_id, &user_id, &txn).await?)
+    } else {
+        None
+    };
+
+    txn.commit().await?;
+    Ok(member)
+}
+
+pub async fn update_role(
+    workspace_id: String,
+    user_id: String,
+    role: WorkspaceRole,
+    db: &DatabaseConnection,
+) -> Result<workspace_member::Model, DbErr> {
+    WorkspaceMember::update(workspace_member::ActiveModel {
+        workspace_id: Set(workspace_id),
+        user_id: Set(user_id),
+        role: Set(role),
+        ..Default::default()
+    })
+    .filter(workspace_member::Column::Role.ne(WorkspaceRole::Owner))
+    .exec(db)
+    .await
+}
+
+pub async fn remove_member(
+    workspace_id: String,
+    user_id: String,
+    db: &DatabaseConnection,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    let result = WorkspaceMember::delete_many()
+        .filter(workspace_member::Column::WorkspaceId.eq(&workspace_id))
+        .filter(workspace_member::Column::UserId.eq(&user_id))
+        .filter(workspace_member::Column::Role.ne(WorkspaceRole::Owner))
+        .exec(&txn)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(DbErr::RecordNotFound(String::from(
+            "Member not found for the given id",
+        )));
+    }
+
+    let owner = WorkspaceMember::find()
+        .filter(workspace_member::Column::WorkspaceId.eq(&workspace_id))
+        .filter(workspace_member::Column::Role.eq(WorkspaceRole::Owner))
+        .one(&txn)
+        .await?
+        .ok_or(DbErr::RecordNotFound(String::from(
+            "Workspace owner not found",
+        )))?;
+    Todo::update_many()
+        .col_expr(todo::Column::UserId, Expr::value(owner.user_id))
+        .filter(todo::Column::WorkspaceId.eq(&workspace_id))
+        .filter(todo::Column::UserId.eq(&user_id))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await?;
+    Ok(())
+}
@@ -2,6 +2,18 @@
 
 pub mod prelude;
 
+pub mod project;
+pub mod sea_orm_active_enums;
 pub mod session;
+pub mod share;
+pub mod subtask;
+pub mod tag;
 pub mod todo;
+pub mod todo_attachment;
+pub mod todo_comment;
+pub mod todo_event;
+pub mod todo_tag;
 pub mod user;
+pub mod workspace;
+pub mod workspace_invitation;
+pub mod workspace_member;
@@ -1,5 +1,16 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+pub use super::project::Entity as Project;
 pub use super::session::Entity as Session;
+pub use super::share::Entity as Share;
+pub use super::subtask::Entity as Subtask;
+pub use super::tag::Entity as Tag;
 pub use super::todo::Entity as Todo;
+pub use super::todo_attachment::Entity as TodoAttachment;
+pub use super::todo_comment::Entity as TodoComment;
+pub use super::todo_event::Entity as TodoEvent;
+pub use super::todo_tag::Entity as TodoTag;
 pub use super::user::Entity as User;
+pub use super::workspace::Entity as Workspace;
+pub use super::workspace_invitation::Entity as WorkspaceInvitation;
+pub use super::workspace_member::Entity as WorkspaceMember;
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+use super::sea_orm_active_enums::Priority;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -15,10 +16,41 @@ pub struct Model {
     pub completed: bool,
     pub created_at: i64,
     pub updated_at: i64,
+    pub due_at: Option<i64>,
+    pub priority: Priority,
+    pub project_id: Option<String>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub recurrence: Option<Json>,
+    pub deleted_at: Option<i64>,
+    pub archived: bool,
+    pub position: String,
+    pub version: i32,
+    pub workspace_id: Option<String>,
+    pub assignee_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Project,
+    #[sea_orm(has_many = "super::share::Entity")]
+    Share,
+    #[sea_orm(has_many = "super::subtask::Entity")]
+    Subtask,
+    #[sea_orm(has_many = "super::todo_attachment::Entity")]
+    TodoAttachment,
+    #[sea_orm(has_many = "super::todo_comment::Entity")]
+    TodoComment,
+    #[sea_orm(has_many = "super::todo_event::Entity")]
+    TodoEvent,
+    #[sea_orm(has_many = "super::todo_tag::Entity")]
+    TodoTag,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::UserId",
@@ -27,6 +59,56 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     User,
+    #[sea_orm(
+        belongs_to = "super::workspace::Entity",
+        from = "Column::WorkspaceId",
+        to = "super::workspace::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Workspace,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl Related<super::share::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Share.def()
+    }
+}
+
+impl Related<super::subtask::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Subtask.def()
+    }
+}
+
+impl Related<super::todo_attachment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TodoAttachment.def()
+    }
+}
+
+impl Related<super::todo_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TodoComment.def()
+    }
+}
+
+impl Related<super::todo_event::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TodoEvent.def()
+    }
+}
+
+impl Related<super::todo_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TodoTag.def()
+    }
 }
 
 impl Related<super::user::Entity> for Entity {
@@ -35,4 +117,19 @@ impl Related<super::user::Entity> for Entity {
     }
 }
 
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::todo_tag::Relation::Tag.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::todo_tag::Relation::Todo.def().rev())
+    }
+}
+
+impl Related<super::workspace::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Workspace.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
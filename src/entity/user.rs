@@ -15,10 +15,28 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::project::Entity")]
+    Project,
     #[sea_orm(has_many = "super::session::Entity")]
     Session,
+    #[sea_orm(has_many = "super::tag::Entity")]
+    Tag,
     #[sea_orm(has_many = "super::todo::Entity")]
     Todo,
+    #[sea_orm(has_many = "super::todo_attachment::Entity")]
+    TodoAttachment,
+    #[sea_orm(has_many = "super::todo_comment::Entity")]
+    TodoComment,
+    #[sea_orm(has_many = "super::todo_event::Entity")]
+    TodoEvent,
+    #[sea_orm(has_many = "super::workspace_member::Entity")]
+    WorkspaceMember,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
 }
 
 impl Related<super::session::Entity> for Entity {
@@ -27,10 +45,40 @@ impl Related<super::session::Entity> for Entity {
     }
 }
 
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tag.def()
+    }
+}
+
 impl Related<super::todo::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Todo.def()
     }
 }
 
+impl Related<super::todo_attachment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TodoAttachment.def()
+    }
+}
+
+impl Related<super::todo_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TodoComment.def()
+    }
+}
+
+impl Related<super::todo_event::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TodoEvent.def()
+    }
+}
+
+impl Related<super::workspace_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WorkspaceMember.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
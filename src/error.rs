@@ -1,4 +1,4 @@
-use crate::token::error::TokenError;
+use crate::{storage::error::StorageError, token::error::TokenError};
 use anyhow::{anyhow, Error as AnyhowError};
 use axum::{http::StatusCode, response::IntoResponse, Json};
 use redis::RedisError;
@@ -30,6 +30,27 @@ pub enum AppError {
     #[error("(IncorrectCredentials): {0}")]
     IncorrectCredentials(#[source] AnyhowError),
 
+    #[error("(Forbidden): {0}")]
+    Forbidden(#[source] AnyhowError),
+
+    #[error("(PayloadTooLarge): {0}")]
+    PayloadTooLarge(#[source] AnyhowError),
+
+    #[error("(UnsupportedMediaType): {0}")]
+    UnsupportedMediaType(#[source] AnyhowError),
+
+    #[error("(UnprocessableEntity): {0}")]
+    UnprocessableEntity(#[source] AnyhowError),
+
+    #[error("(PreconditionFailed): {0}")]
+    PreconditionFailed(#[source] AnyhowError),
+
+    #[error("(IdempotencyKeyInUse): {0}")]
+    IdempotencyKeyInUse(#[source] AnyhowError),
+
+    #[error("(IdempotencyKeyReused): {0}")]
+    IdempotencyKeyReused(#[source] AnyhowError),
+
     #[error(transparent)]
     Validation(#[from] ValidationErrors),
 
@@ -41,6 +62,7 @@ impl AppError {
     pub fn from_db_error(err: DbErr) -> Self {
         match err {
             DbErr::RecordNotFound(err) => AppError::NotFound(anyhow!(err)),
+            DbErr::RecordNotUpdated => AppError::NotFound(anyhow!("Record not found to update")),
             err => {
                 if is_unique_violation(&err) {
                     AppError::UniqueViolation(err.into())
@@ -51,21 +73,41 @@ impl AppError {
         }
     }
 
+    /// Versioned todo writes report a missing todo as `DbErr::RecordNotFound`,
+    /// so a write that misses its row means the todo changed after the client
+    /// read it
+    pub fn from_versioned_db_error(version: Option<i32>) -> impl Fn(DbErr) -> Self {
+        move |err| match (err, version) {
+            (DbErr::RecordNotUpdated, Some(version)) => AppError::PreconditionFailed(anyhow!(
+                "Todo has been modified since version {}",
+                version
+            )),
+            (err, _) => AppError::from_db_error(err),
+        }
+    }
+
     pub fn from_token_error(err: TokenError) -> Self {
         match err {
             TokenError::MissingClaims(source)
             | TokenError::InvalidFormat(source)
             | TokenError::Parsing(source)
-            | TokenError::Validation(source) => AppError::Unauthorized(source.into()),
+            | TokenError::Validation(source) => AppError::Unauthorized(source),
 
             _ => AppError::Other(err.into()),
         }
     }
+
+    pub fn from_storage_error(err: StorageError) -> Self {
+        match err {
+            StorageError::NotFound(_) => AppError::NotFound(err.into()),
+            _ => AppError::Other(err.into()),
+        }
+    }
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> axum::response::Response {
-        let (status, message) = match self {
+impl AppError {
+    pub fn into_parts(self) -> (StatusCode, String) {
+        match self {
             AppError::Database(err) => {
                 log::error!("Database error: {:?}", err);
                 (
@@ -99,6 +141,52 @@ impl IntoResponse for AppError {
                     String::from("credentials are not valid"),
                 )
             }
+            AppError::Forbidden(err) => {
+                log::error!("{err}");
+                (StatusCode::FORBIDDEN, String::from("forbidden"))
+            }
+            AppError::PayloadTooLarge(err) => {
+                log::error!("{err}");
+                (
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    String::from("payload too large"),
+                )
+            }
+            AppError::UnsupportedMediaType(err) => {
+                log::error!("{err}");
+                (
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    String::from("unsupported media type"),
+                )
+            }
+            AppError::UnprocessableEntity(err) => {
+                log::error!("{err}");
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    String::from("unprocessable entity"),
+                )
+            }
+            AppError::PreconditionFailed(err) => {
+                log::error!("{err}");
+                (
+                    StatusCode::PRECONDITION_FAILED,
+                    String::from("resource has been modified"),
+                )
+            }
+            AppError::IdempotencyKeyInUse(err) => {
+                log::error!("{err}");
+                (
+                    StatusCode::CONFLICT,
+                    String::from("a request with this idempotency key is in progress"),
+                )
+            }
+            AppError::IdempotencyKeyReused(err) => {
+                log::error!("{err}");
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    String::from("idempotency key was used for a different request"),
+                )
+            }
             AppError::UniqueViolation(err) => {
                 log::error!("{err}");
                 (StatusCode::CONFLICT, String::from("already exists"))
@@ -128,7 +216,13 @@ impl IntoResponse for AppError {
                     String::from("something went wrong"),
                 )
             }
-        };
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = self.into_parts();
 
         (
             status,
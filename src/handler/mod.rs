@@ -1,3 +1,11 @@
 pub mod auth;
+pub mod project;
+pub mod share;
+pub mod subtask;
+pub mod tag;
 pub mod todo;
+pub mod todo_attachment;
+pub mod todo_comment;
 pub mod user;
+pub mod v1;
+pub mod workspace;
@@ -0,0 +1,97 @@
+use crate::{
+    config::state::AppState,
+    database,
+    error::AppError,
+    model::project::{
+        ArchiveProjectReq, CreateProjectReq, DeleteProjectReq, ProjectListQuery, RenameProjectReq,
+    },
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::json;
+use validator::Validate;
+
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<CreateProjectReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let project = database::project::create(user_id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "project": project
+    })))
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Query(query): Query<ProjectListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let projects = database::project::find_by_user_id(
+        user_id,
+        query.include_archived.unwrap_or(false),
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "projects": projects
+    })))
+}
+
+pub async fn rename(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<RenameProjectReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let project = database::project::rename(payload.id, user_id, payload.name, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "project": project
+    })))
+}
+
+pub async fn archive(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<ArchiveProjectReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let project = database::project::archive(payload.id, user_id, payload.archived, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "project": project
+    })))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<DeleteProjectReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    database::project::delete(payload.id, user_id, payload.mode, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
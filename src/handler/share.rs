@@ -0,0 +1,127 @@
+use crate::{
+    config::state::AppState,
+    database,
+    error::AppError,
+    model::share::{CreateShareReq, ShareIDReq, UpdateShareReq},
+};
+use anyhow::anyhow;
+use axum::{extract::State, response::IntoResponse, Extension, Json};
+use serde_json::json;
+use validator::Validate;
+
+pub async fn invite(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<CreateShareReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let invitee = database::user::find_by_email(&payload.email, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?
+        .ok_or_else(|| AppError::NotFound(anyhow!("No user registered with the given email")))?;
+    if invitee.id == user_id {
+        return Err(AppError::BadRequest(anyhow!(
+            "A todo can not be shared with its owner"
+        )));
+    }
+
+    let share = database::share::invite(user_id, invitee.id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "share": share
+    })))
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let shares = database::share::find_by_owner_id(user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "shares": shares
+    })))
+}
+
+pub async fn invitations(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let invitations = database::share::find_invitations(user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "invitations": invitations
+    })))
+}
+
+pub async fn accept(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<ShareIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let share = database::share::respond(payload.id, user_id, true, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "share": share
+    })))
+}
+
+pub async fn decline(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<ShareIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let share = database::share::respond(payload.id, user_id, false, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "share": share
+    })))
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<UpdateShareReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let share =
+        database::share::update_permission(payload.id, user_id, payload.permission, &state.db)
+            .await
+            .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "share": share
+    })))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<ShareIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    database::share::delete(payload.id, user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
@@ -0,0 +1,93 @@
+use crate::{
+    config::state::AppState,
+    database,
+    error::AppError,
+    model::subtask::{AddSubtaskReq, ReorderSubtaskReq, SubtaskIDReq, SubtaskListQuery},
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::json;
+use validator::Validate;
+
+pub async fn add(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<AddSubtaskReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let subtask = database::subtask::add(user_id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "subtask": subtask
+    })))
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Query(query): Query<SubtaskListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate()?;
+
+    let subtasks = database::subtask::find_by_todo_id(query.todo_id, user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "subtasks": subtasks
+    })))
+}
+
+pub async fn toggle(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<SubtaskIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let subtask = database::subtask::toggle(payload.id, user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "subtask": subtask
+    })))
+}
+
+pub async fn reorder(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<ReorderSubtaskReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let subtasks = database::subtask::reorder(user_id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "subtasks": subtasks
+    })))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<SubtaskIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    database::subtask::delete(payload.id, user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
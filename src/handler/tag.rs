@@ -0,0 +1,102 @@
+use crate::{
+    config::state::AppState,
+    database,
+    error::AppError,
+    model::tag::{CreateTagReq, TagIDReq, TodoTagReq, UpdateTagReq},
+};
+use axum::{extract::State, response::IntoResponse, Extension, Json};
+use serde_json::json;
+use validator::Validate;
+
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<CreateTagReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let tag = database::tag::create(user_id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "tag": tag
+    })))
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let tags = database::tag::find_by_user_id(user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "tags": tags
+    })))
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<UpdateTagReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let tag = database::tag::update(user_id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "tag": tag
+    })))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<TagIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    database::tag::delete(payload.id, user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
+
+pub async fn attach(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<TodoTagReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    database::tag::attach(user_id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
+
+pub async fn detach(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<TodoTagReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    database::tag::detach(user_id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
@@ -1,100 +1,687 @@
 use crate::{
     config::state::AppState,
     database,
+    entity::{sea_orm_active_enums::SharePermission, todo},
     error::AppError,
-    model::todo::{CreateTodoReq, TodoIDReq, UpdateTodoReq},
-    utils::paginate::Paginator,
+    middleware::auth::{ActiveWorkspace, SessionId},
+    model::todo::{
+        ArchiveTodoReq, AssignTodoReq, Assignee, BulkOperation, BulkResult, BulkTodoReq,
+        CreateTodoReq, MarkTodoReq, MoveTodoReq, TodoFilter, TodoIDReq, TodoIDsQuery,
+        TodoSearchQuery, UpdateTodoReq,
+    },
+    model::undo::{UndoRecord, UndoReq},
+    utils::{
+        etag::{etag, if_match},
+        paginate::{Cursor, Paginator},
+    },
 };
+use anyhow::anyhow;
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
     Extension, Json,
 };
+use sea_orm::{ConnectionTrait, DbErr, TransactionTrait};
 use serde::Deserialize;
 use serde_json::json;
-use validator::Validate;
+use std::borrow::Cow;
+use validator::{Validate, ValidationError, ValidationErrors};
 
 pub async fn create(
     State(state): State<AppState>,
     Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
+    Extension(workspace): Extension<ActiveWorkspace>,
     Json(payload): Json<CreateTodoReq>,
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate()?;
+    let workspace_id = workspace.writable_id()?;
 
-    database::todo::create(user_id, payload, &state.db)
+    database::todo::create(
+        user_id,
+        workspace_id,
+        Some(session_id.0),
+        payload,
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
+
+/// Checks that the user may access the todo with `permission` and returns the
+/// id of its owner, which the todo queries are scoped to
+pub(crate) async fn authorize(
+    id: &str,
+    user_id: &str,
+    permission: SharePermission,
+    state: &AppState,
+) -> Result<String, AppError> {
+    database::share::authorize(id, user_id, permission, &state.db)
+        .await
+        .map(|todo| todo.user_id)
+        .map_err(AppError::from_db_error)
+}
+
+/// Like `authorize` for marking a todo, which its assignee may do as well
+async fn authorize_mark(id: &str, user_id: &str, state: &AppState) -> Result<String, AppError> {
+    let todo = database::share::authorize(id, user_id, SharePermission::Viewer, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+    if todo.user_id == user_id || todo.assignee_id.as_deref() == Some(user_id) {
+        return Ok(todo.user_id);
+    }
+
+    authorize(id, user_id, SharePermission::Editor, state).await
+}
+
+pub async fn find(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    TodoIDReq { id: id.clone() }.validate()?;
+
+    let owner_id = authorize(&id, &user_id, SharePermission::Viewer, &state).await?;
+    let todo = database::todo::find_owned(&id, &owner_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+    let assignee = database::todo::find_assignees(std::slice::from_ref(&todo), &state.db)
+        .await
+        .map_err(AppError::from_db_error)?
+        .into_values()
+        .next();
+
+    Ok((
+        [(header::ETAG, etag(todo.version))],
+        Json(json!({
+            "todo": todo,
+            "assignee": assignee
+        })),
+    ))
+}
+
+pub async fn find_many(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Query(query): Query<TodoIDsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate()?;
+
+    let todos = database::todo::find_by_ids(query.ids(), user_id, &state.db)
         .await
         .map_err(AppError::from_db_error)?;
 
     Ok(Json(json!({
-        "status": "ok"
+        "todos": todos
     })))
 }
 
-#[derive(Deserialize, Default)]
+#[derive(Deserialize, Default, Validate)]
 pub struct Pagination {
-    page: Option<u64>,
-    limit: Option<u64>,
+    #[validate(range(min = 1, message = "page must be at least 1"))]
+    pub page: Option<u64>,
+
+    #[validate(range(min = 1, max = 100, message = "limit must be between 1 and 100"))]
+    pub limit: Option<u64>,
+
+    pub cursor: Option<String>,
+}
+
+impl Pagination {
+    pub fn take(&self) -> u64 {
+        self.limit.unwrap_or(5)
+    }
+
+    pub fn skip(&self) -> u64 {
+        (self.page.unwrap_or(1) - 1) * self.take()
+    }
 }
 
 pub async fn list(
     State(state): State<AppState>,
     Extension(user_id): Extension<String>,
+    Extension(workspace): Extension<ActiveWorkspace>,
     Query(query): Query<Pagination>,
+    Query(filter): Query<TodoFilter>,
 ) -> Result<impl IntoResponse, AppError> {
-    let take = query.limit.unwrap_or(5);
-    let skip = (query.page.unwrap_or(1) - 1) * take;
+    query.validate()?;
+    filter.validate()?;
 
-    let todos = database::todo::find_by_user_id(user_id, Paginator { skip, take }, &state.db)
-        .await
-        .map_err(AppError::from_db_error)?;
+    let cursor = match query.cursor.as_deref() {
+        Some(cursor) => {
+            if query.page.is_some() {
+                let mut errors = ValidationErrors::new();
+                errors.add(
+                    "cursor",
+                    ValidationError::new("cursor")
+                        .with_message(Cow::Borrowed("cursor can not be used along with page")),
+                );
+                return Err(AppError::Validation(errors));
+            }
+
+            // A cursor only makes sense in the ordering it was taken from
+            Some(
+                Cursor::decode(cursor)
+                    .filter(|cursor| database::todo::accepts_cursor(&filter, cursor))
+                    .ok_or_else(|| AppError::BadRequest(anyhow!("Invalid cursor provided")))?,
+            )
+        }
+        None => None,
+    };
+
+    let take = query.take();
+    let skip = query.skip();
+
+    let todos = database::todo::find_by_user_id(
+        user_id,
+        workspace.id(),
+        filter,
+        Paginator { skip, take, cursor },
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
 
     Ok(Json(todos))
 }
 
+pub async fn search(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+    Query(query): Query<Pagination>,
+    Query(search): Query<TodoSearchQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate()?;
+    search.validate()?;
+
+    let take = query.take();
+    let skip = query.skip();
+
+    let results = database::todo::search(
+        user_id,
+        workspace.id(),
+        search,
+        Paginator {
+            skip,
+            take,
+            ..Default::default()
+        },
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    Ok(Json(results))
+}
+
+async fn apply<C>(
+    user_id: String,
+    workspace_id: Option<String>,
+    session_id: Option<String>,
+    operation: BulkOperation,
+    undo: &mut UndoRecord,
+    db: &C,
+) -> Result<Option<todo::Model>, AppError>
+where
+    C: ConnectionTrait + TransactionTrait,
+{
+    operation.validate()?;
+
+    let mut applied = UndoRecord::default();
+    let savepoint = db.begin().await.map_err(AppError::from_db_error)?;
+    let result = match operation {
+        BulkOperation::Create(data) => {
+            database::todo::create(user_id, workspace_id, session_id, data, &savepoint)
+                .await
+                .map(|todo| {
+                    applied.push_created(&todo);
+                    Some(todo)
+                })
+        }
+        BulkOperation::Update(data) => database::todo::update(
+            user_id.clone(),
+            &user_id,
+            session_id,
+            data,
+            None,
+            &savepoint,
+        )
+        .await
+        .map(|change| {
+            applied.push_change(&change);
+            Some(change.after)
+        }),
+        BulkOperation::Mark(data) => database::todo::mark(
+            data.id,
+            user_id.clone(),
+            &user_id,
+            session_id,
+            data.cascade,
+            None,
+            &savepoint,
+        )
+        .await
+        .map(|change| {
+            applied.push_change(&change);
+            Some(change.after)
+        }),
+        BulkOperation::Delete(data) => {
+            database::todo::delete(data.id, user_id, session_id, None, &savepoint)
+                .await
+                .map(|change| {
+                    applied.push_change(&change);
+                    None
+                })
+        }
+    };
+
+    match result {
+        Ok(todo) => {
+            savepoint.commit().await.map_err(AppError::from_db_error)?;
+            undo.append(applied);
+            Ok(todo)
+        }
+        Err(err) => {
+            savepoint
+                .rollback()
+                .await
+                .map_err(AppError::from_db_error)?;
+            Err(AppError::from_db_error(err))
+        }
+    }
+}
+
+/// Keeps an undo record for the action and returns its token, `None` when
+/// nothing was changed
+pub(crate) async fn save_undo(
+    user_id: &str,
+    record: UndoRecord,
+    state: &AppState,
+) -> Result<Option<String>, AppError> {
+    if record.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        database::undo::save(user_id, &record, &state.rd).await?,
+    ))
+}
+
+pub async fn bulk(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+    Json(payload): Json<BulkTodoReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let workspace_id = workspace.writable_id()?;
+
+    let txn = state.db.begin().await.map_err(AppError::from_db_error)?;
+
+    let mut undo = UndoRecord::default();
+    let mut results = Vec::with_capacity(payload.operations.len());
+    for (index, operation) in payload.operations.into_iter().enumerate() {
+        let session_id = Some(session_id.0.clone());
+        let workspace_id = workspace_id.clone();
+        results.push(
+            match apply(
+                user_id.clone(),
+                workspace_id,
+                session_id,
+                operation,
+                &mut undo,
+                &txn,
+            )
+            .await
+            {
+                Ok(todo) => BulkResult {
+                    index,
+                    status: StatusCode::OK.as_u16(),
+                    todo,
+                    error: None,
+                },
+                Err(err) => {
+                    let (status, message) = err.into_parts();
+                    BulkResult {
+                        index,
+                        status: status.as_u16(),
+                        todo: None,
+                        error: Some(message),
+                    }
+                }
+            },
+        );
+    }
+
+    txn.commit().await.map_err(AppError::from_db_error)?;
+    let undo_token = save_undo(&user_id, undo, &state).await?;
+
+    Ok(Json(json!({
+        "results": results,
+        "undo_token": undo_token
+    })))
+}
+
 pub async fn update(
     State(state): State<AppState>,
     Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateTodoReq>,
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate()?;
+    let version = if_match(&headers)?;
 
-    database::todo::update(user_id, payload, &state.db)
-        .await
-        .map_err(AppError::from_db_error)?;
+    let owner_id = authorize(&payload.id, &user_id, SharePermission::Editor, &state).await?;
+    let change = database::todo::update(
+        owner_id,
+        &user_id,
+        Some(session_id.0),
+        payload,
+        version,
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_versioned_db_error(version))?;
+
+    let mut undo = UndoRecord::default();
+    undo.push_change(&change);
+    let undo_token = save_undo(&user_id, undo, &state).await?;
+
+    Ok((
+        [(header::ETAG, etag(change.after.version))],
+        Json(json!({
+            "todo": change.after,
+            "undo_token": undo_token
+        })),
+    ))
+}
+
+pub async fn mark(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
+    headers: HeaderMap,
+    Json(payload): Json<MarkTodoReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let version = if_match(&headers)?;
+
+    let owner_id = authorize_mark(&payload.id, &user_id, &state).await?;
+    let change = database::todo::mark(
+        payload.id,
+        owner_id,
+        &user_id,
+        Some(session_id.0),
+        payload.cascade,
+        version,
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_versioned_db_error(version))?;
+
+    Ok((
+        [(header::ETAG, etag(change.after.version))],
+        Json(json!({
+            "todo": change.after
+        })),
+    ))
+}
+
+pub async fn reposition(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<MoveTodoReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let todo = database::todo::reposition(
+        payload.id,
+        user_id,
+        payload.after_id,
+        payload.before_id,
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?
+    .ok_or_else(|| AppError::BadRequest(anyhow!("after_id must be ordered before before_id")))?;
 
     Ok(Json(json!({
-        "status": "ok"
+        "todo": todo
     })))
 }
 
-pub async fn mark(
+pub async fn delete(
     State(state): State<AppState>,
     Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
+    headers: HeaderMap,
     Json(payload): Json<TodoIDReq>,
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate()?;
+    let version = if_match(&headers)?;
+
+    let change = database::todo::delete(
+        payload.id,
+        user_id.clone(),
+        Some(session_id.0),
+        version,
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_versioned_db_error(version))?;
+
+    let mut undo = UndoRecord::default();
+    undo.push_change(&change);
+    let undo_token = save_undo(&user_id, undo, &state).await?;
+
+    Ok(Json(json!({
+        "status": "ok",
+        "undo_token": undo_token
+    })))
+}
+
+pub async fn assign(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
+    Json(payload): Json<AssignTodoReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let assignee = match &payload.email {
+        Some(email) => Some(
+            database::user::find_by_email(email, &state.db)
+                .await
+                .map_err(AppError::from_db_error)?
+                .ok_or_else(|| {
+                    AppError::NotFound(anyhow!("No user registered with the given email"))
+                })?,
+        ),
+        None => None,
+    };
+
+    if let Some(assignee) = &assignee {
+        let todo = database::todo::find_owned(&payload.id, &user_id, &state.db)
+            .await
+            .map_err(AppError::from_db_error)?;
+        if let Some(workspace_id) = &todo.workspace_id {
+            match database::workspace::find_membership(workspace_id, &assignee.id, &state.db).await
+            {
+                Ok(_) => {}
+                Err(DbErr::RecordNotFound(_)) => {
+                    return Err(AppError::UnprocessableEntity(anyhow!(
+                        "User {} is not a member of workspace {}",
+                        assignee.id,
+                        workspace_id
+                    )))
+                }
+                Err(err) => return Err(AppError::from_db_error(err)),
+            }
+        }
+    }
+
+    let change = database::todo::assign(
+        payload.id,
+        user_id,
+        assignee.as_ref().map(|assignee| assignee.id.clone()),
+        Some(session_id.0),
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    Ok((
+        [(header::ETAG, etag(change.after.version))],
+        Json(json!({
+            "todo": change.after,
+            "assignee": assignee.map(Assignee::from)
+        })),
+    ))
+}
+
+pub async fn archive(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<ArchiveTodoReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
 
-    database::todo::mark(payload.id, user_id, &state.db)
+    let todo = database::todo::archive(payload.id, user_id, payload.archived, &state.db)
         .await
         .map_err(AppError::from_db_error)?;
 
     Ok(Json(json!({
-        "status": "ok"
+        "todo": todo
     })))
 }
 
-pub async fn delete(
+pub async fn archive_completed(
     State(state): State<AppState>,
     Extension(user_id): Extension<String>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+) -> Result<impl IntoResponse, AppError> {
+    let archived = database::todo::archive_completed(user_id, workspace.id(), &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "archived": archived
+    })))
+}
+
+pub async fn history(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Path(id): Path<String>,
+    Query(query): Query<Pagination>,
+) -> Result<impl IntoResponse, AppError> {
+    TodoIDReq { id: id.clone() }.validate()?;
+    query.validate()?;
+
+    let owner_id = authorize(&id, &user_id, SharePermission::Viewer, &state).await?;
+
+    let take = query.take();
+    let skip = query.skip();
+
+    let events = database::todo_event::find_by_todo_id(
+        id,
+        owner_id,
+        Paginator {
+            skip,
+            take,
+            ..Default::default()
+        },
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    Ok(Json(events))
+}
+
+pub async fn trash(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+    Query(query): Query<Pagination>,
+) -> Result<impl IntoResponse, AppError> {
+    query.validate()?;
+
+    let take = query.take();
+    let skip = query.skip();
+
+    let todos = database::todo::find_deleted_by_user_id(
+        user_id,
+        workspace.id(),
+        Paginator {
+            skip,
+            take,
+            ..Default::default()
+        },
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    Ok(Json(todos))
+}
+
+pub async fn restore(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
     Json(payload): Json<TodoIDReq>,
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate()?;
 
-    database::todo::delete(payload.id, user_id, &state.db)
+    let todo = database::todo::restore(payload.id, user_id, Some(session_id.0), &state.db)
         .await
         .map_err(AppError::from_db_error)?;
 
     Ok(Json(json!({
-        "status": "ok"
+        "todo": todo
+    })))
+}
+
+pub async fn undo(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
+    Json(payload): Json<UndoReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let record = database::undo::find(&user_id, &payload.undo_token, &state.rd)
+        .await?
+        .ok_or_else(|| AppError::NotFound(anyhow!("Undo token expired or already used")))?;
+
+    for snapshot in record.snapshots.iter().chain(record.created.iter()) {
+        authorize(&snapshot.todo.id, &user_id, SharePermission::Editor, &state).await?;
+    }
+
+    let todos = database::todo::undo(&user_id, Some(session_id.0), record, &state.db)
+        .await
+        .map_err(|err| match err {
+            DbErr::RecordNotUpdated => AppError::PreconditionFailed(anyhow!(
+                "Todo has been modified since the undone action"
+            )),
+            err => AppError::from_db_error(err),
+        })?;
+    // The reverted todos moved past the versions in the record, so a token
+    // left behind can not be applied again
+    if let Err(err) = database::undo::remove(&user_id, &payload.undo_token, &state.rd).await {
+        log::error!("Failed to remove the used undo token: {}", err);
+    }
+
+    Ok(Json(json!({
+        "todos": todos
     })))
 }
@@ -0,0 +1,269 @@
+use crate::{
+    config::{state::AppState, ENV},
+    database,
+    entity::{sea_orm_active_enums::SharePermission, todo_attachment},
+    error::AppError,
+    handler::todo::authorize,
+    model::{
+        todo::TodoIDReq,
+        todo_attachment::{AttachmentIDReq, DownloadQuery, StoredFile, TodoAttachmentItem},
+    },
+    storage::traits::Storage,
+    token::{
+        claims::Claims,
+        service::create_token,
+        traits::Token,
+        types::{attachment::Attachment, params::TokenParams, response::TokenResponse},
+        TokenType,
+    },
+    utils::time,
+};
+use anyhow::anyhow;
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::json;
+use urlencoding::encode;
+use validator::Validate;
+
+/// Name of the multipart field carrying the file
+const FILE_FIELD: &str = "file";
+
+/// Room left in the request body for the multipart boundaries and headers
+pub const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
+fn from_multipart_error(err: MultipartError) -> AppError {
+    if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::PayloadTooLarge(anyhow!(err))
+    } else {
+        AppError::BadRequest(anyhow!(err))
+    }
+}
+
+/// Keeps the last segment of the name the client sent, without control
+/// characters
+fn sanitize_file_name(file_name: &str) -> String {
+    let file_name: String = file_name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILE_NAME_LENGTH)
+        .collect();
+
+    match file_name.trim() {
+        "" | "." | ".." => String::from("attachment"),
+        file_name => file_name.to_owned(),
+    }
+}
+
+/// Signs a download URL for the attachment that works until the attachment
+/// token expires
+async fn with_download_url(
+    attachment: todo_attachment::Model,
+    state: &AppState,
+) -> Result<TodoAttachmentItem, AppError> {
+    let token = create_token(
+        Attachment::new(state.clone(), attachment.id.clone()),
+        TokenParams::default(),
+    )
+    .await
+    .map(|token| {
+        let TokenResponse::Attachment(token) = token else {
+            unreachable!("Attachment token is expected");
+        };
+        token
+    })?;
+
+    Ok(TodoAttachmentItem {
+        attachment,
+        download_url: format!("/attachment/download?token={}", token),
+        download_url_expires_at: time::now() + ENV.attachment_token_expiration as i64,
+    })
+}
+
+/// Uploads the `file` field of a multipart body as an attachment of the todo,
+/// within the size and content type limits of the environment
+pub async fn upload(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Path(id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    TodoIDReq { id: id.clone() }.validate()?;
+    authorize(&id, &user_id, SharePermission::Editor, &state).await?;
+
+    let mut field = loop {
+        let field = multipart
+            .next_field()
+            .await
+            .map_err(from_multipart_error)?
+            .ok_or_else(|| AppError::BadRequest(anyhow!("Missing the {} field", FILE_FIELD)))?;
+        if field.name() == Some(FILE_FIELD) {
+            break field;
+        }
+    };
+
+    let content_type = field
+        .content_type()
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|content_type| content_type.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !ENV.attachment_type_allowed(&content_type) {
+        return Err(AppError::UnsupportedMediaType(anyhow!(
+            "Attachments of type {:?} are not allowed",
+            content_type
+        )));
+    }
+    let file_name = sanitize_file_name(field.file_name().unwrap_or_default());
+
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(from_multipart_error)? {
+        if (data.len() + chunk.len()) as u64 > ENV.attachment_max_size {
+            return Err(AppError::PayloadTooLarge(anyhow!(
+                "Attachment is larger than {} bytes",
+                ENV.attachment_max_size
+            )));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    if data.is_empty() {
+        return Err(AppError::BadRequest(anyhow!("Attachment is empty")));
+    }
+
+    let file = StoredFile {
+        file_name,
+        content_type,
+        size: data.len() as i64,
+        storage_key: format!("{}/{}", id, ulid::Ulid::new()),
+    };
+    state
+        .storage
+        .put(&file.storage_key, &file.content_type, data.into())
+        .await
+        .map_err(AppError::from_storage_error)?;
+
+    let storage_key = file.storage_key.clone();
+    let attachment = match database::todo_attachment::add(id, user_id, file, &state.db).await {
+        Ok(attachment) => attachment,
+        Err(err) => {
+            if let Err(err) = state.storage.delete(&storage_key).await {
+                log::error!("Failed to remove the unrecorded attachment: {}", err);
+            }
+            return Err(AppError::from_db_error(err));
+        }
+    };
+
+    Ok(Json(json!({
+        "attachment": with_download_url(attachment, &state).await?
+    })))
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    TodoIDReq { id: id.clone() }.validate()?;
+    authorize(&id, &user_id, SharePermission::Viewer, &state).await?;
+
+    let attachments = database::todo_attachment::find_by_todo_id(id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    let mut result = Vec::with_capacity(attachments.len());
+    for attachment in attachments {
+        result.push(with_download_url(attachment, &state).await?);
+    }
+
+    Ok(Json(json!({
+        "attachments": result
+    })))
+}
+
+/// Deletes an attachment, which takes the same access as editing the todo
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<AttachmentIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let attachment = database::todo_attachment::find_by_id(payload.id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+    authorize(
+        &attachment.todo_id,
+        &user_id,
+        SharePermission::Editor,
+        &state,
+    )
+    .await?;
+
+    database::todo_attachment::delete(attachment.id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+    // The record is gone, so a file left behind is only wasted space
+    if let Err(err) = state.storage.delete(&attachment.storage_key).await {
+        log::error!("Failed to remove the attachment file: {}", err);
+    }
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
+
+/// Serves the file of a signed download URL, the token stands in for the
+/// access token so the URL works in places that can not send headers
+pub async fn download(
+    State(state): State<AppState>,
+    Query(query): Query<DownloadQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let claims = Attachment::default(state.clone())
+        .verify(query.token, TokenType::Attachment)
+        .await
+        .map_err(AppError::from_token_error)?;
+
+    let attachment = database::todo_attachment::find_by_id(claims.sub().to_owned(), &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+    let data = state
+        .storage
+        .get(&attachment.storage_key)
+        .await
+        .map_err(AppError::from_storage_error)?;
+
+    let fallback_name: String = attachment
+        .file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && c != '"' && c != '\\' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+                    fallback_name,
+                    encode(&attachment.file_name)
+                ),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, String::from("nosniff")),
+        ],
+        data,
+    ))
+}
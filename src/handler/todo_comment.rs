@@ -0,0 +1,121 @@
+use crate::{
+    config::state::AppState,
+    database,
+    entity::sea_orm_active_enums::SharePermission,
+    error::AppError,
+    handler::todo::{authorize, Pagination},
+    model::{
+        todo::TodoIDReq,
+        todo_comment::{AddCommentReq, CommentIDReq, EditCommentReq},
+    },
+    utils::paginate::Paginator,
+};
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::json;
+use validator::Validate;
+
+/// Anyone who can see a todo can discuss it
+pub async fn add(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<AddCommentReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    authorize(&payload.todo_id, &user_id, SharePermission::Viewer, &state).await?;
+
+    let comment = database::todo_comment::add(user_id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "comment": comment
+    })))
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Path(id): Path<String>,
+    Query(query): Query<Pagination>,
+) -> Result<impl IntoResponse, AppError> {
+    TodoIDReq { id: id.clone() }.validate()?;
+    query.validate()?;
+    authorize(&id, &user_id, SharePermission::Viewer, &state).await?;
+
+    let take = query.take();
+    let skip = query.skip();
+
+    let comments = database::todo_comment::find_by_todo_id(
+        id,
+        Paginator {
+            skip,
+            take,
+            ..Default::default()
+        },
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    Ok(Json(comments))
+}
+
+pub async fn edit(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<EditCommentReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let comment = database::todo_comment::find_by_id(payload.id.clone(), &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+    authorize(&comment.todo_id, &user_id, SharePermission::Viewer, &state).await?;
+    if comment.user_id != user_id {
+        return Err(AppError::Forbidden(anyhow!(
+            "Comment {} can only be edited by its author",
+            comment.id
+        )));
+    }
+
+    let comment = database::todo_comment::edit(payload.id, user_id, payload.body, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "comment": comment
+    })))
+}
+
+/// Deletes a comment, which the owner of the todo can do as well as its author
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<CommentIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let comment = database::todo_comment::find_by_id(payload.id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+    let owner_id = authorize(&comment.todo_id, &user_id, SharePermission::Viewer, &state).await?;
+    if comment.user_id != user_id && owner_id != user_id {
+        return Err(AppError::Forbidden(anyhow!(
+            "Comment {} can only be deleted by its author or the todo owner",
+            comment.id
+        )));
+    }
+
+    database::todo_comment::delete(comment.id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
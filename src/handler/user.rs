@@ -4,13 +4,13 @@ use crate::token::cookies::CookieManager;
 use crate::token::traits::Token;
 use crate::token::types::refresh::Refresh;
 use crate::token::{constants, TokenType};
+use crate::utils::avatar;
 use crate::{config::state::AppState, error::AppError};
 use anyhow::anyhow;
 use axum::http::HeaderMap;
 use axum::Json;
 use axum::{extract::State, response::IntoResponse, Extension};
 use serde_json::json;
-use urlencoding::encode;
 use validator::Validate;
 
 pub async fn profile(
@@ -27,7 +27,7 @@ pub async fn profile(
             "id": user.id,
             "email": user.email,
             "name": user.name,
-            "photo_url": format!("https://api.dicebear.com/9.x/notionists/svg?seed={}", encode(&user.name))
+            "photo_url": avatar::photo_url(&user.name)
         },
     })))
 }
@@ -0,0 +1,118 @@
+use crate::{
+    config::state::AppState,
+    database,
+    error::AppError,
+    handler::todo::{self, Pagination},
+    middleware::auth::{ActiveWorkspace, SessionId},
+    model::{
+        todo::{CreateTodoReq, TodoFilter, TodoIDReq, UpdateTodoReq},
+        undo::UndoRecord,
+    },
+    utils::etag::{etag, if_match},
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::json;
+use validator::Validate;
+
+pub async fn list(
+    state: State<AppState>,
+    user_id: Extension<String>,
+    workspace: Extension<ActiveWorkspace>,
+    query: Query<Pagination>,
+    filter: Query<TodoFilter>,
+) -> Result<impl IntoResponse, AppError> {
+    todo::list(state, user_id, workspace, query, filter).await
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+    Json(payload): Json<CreateTodoReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let workspace_id = workspace.writable_id()?;
+
+    let todo = database::todo::create(
+        user_id,
+        workspace_id,
+        Some(session_id.0),
+        payload,
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::LOCATION,
+        HeaderValue::from_str(&format!("/v1/todos/{}", todo.id))
+            .map_err(|err| AppError::Other(err.into()))?,
+    );
+    headers.insert(header::ETAG, etag(todo.version));
+
+    Ok((
+        StatusCode::CREATED,
+        headers,
+        Json(json!({
+            "todo": todo
+        })),
+    ))
+}
+
+pub async fn find(
+    state: State<AppState>,
+    user_id: Extension<String>,
+    id: Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    todo::find(state, user_id, id).await
+}
+
+pub async fn update(
+    state: State<AppState>,
+    user_id: Extension<String>,
+    session_id: Extension<SessionId>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(mut payload): Json<UpdateTodoReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.id = id;
+
+    todo::update(state, user_id, session_id, headers, Json(payload)).await
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(session_id): Extension<SessionId>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    TodoIDReq { id: id.clone() }.validate()?;
+    let version = if_match(&headers)?;
+
+    let change =
+        database::todo::delete(id, user_id.clone(), Some(session_id.0), version, &state.db)
+            .await
+            .map_err(AppError::from_versioned_db_error(version))?;
+
+    let mut undo = UndoRecord::default();
+    undo.push_change(&change);
+    let undo_token = todo::save_undo(&user_id, undo, &state).await?;
+
+    let mut headers = HeaderMap::new();
+    if let Some(undo_token) = undo_token {
+        headers.insert(
+            "undo-token",
+            HeaderValue::from_str(&undo_token).map_err(|err| AppError::Other(err.into()))?,
+        );
+    }
+
+    Ok((StatusCode::NO_CONTENT, headers))
+}
@@ -0,0 +1,241 @@
+use crate::{
+    config::state::AppState,
+    database,
+    entity::{sea_orm_active_enums::WorkspaceRole, workspace_member},
+    error::AppError,
+    middleware::auth::ActiveWorkspace,
+    model::workspace::{
+        CreateWorkspaceReq, InvitationIDReq, InviteMemberReq, MemberIDReq, RenameWorkspaceReq,
+        UpdateMemberReq,
+    },
+};
+use anyhow::anyhow;
+use axum::{extract::State, response::IntoResponse, Extension, Json};
+use sea_orm::DbErr;
+use serde_json::json;
+use validator::Validate;
+
+/// Membership of the active workspace, when it lets the user manage it
+fn managed(workspace: &ActiveWorkspace) -> Result<&workspace_member::Model, AppError> {
+    let member = workspace.require()?;
+    if !member.role.can_manage() {
+        return Err(AppError::Forbidden(anyhow!(
+            "Only owners and admins can manage workspace {}",
+            member.workspace_id
+        )));
+    }
+
+    Ok(member)
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<CreateWorkspaceReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let workspace = database::workspace::create(user_id, payload, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "workspace": workspace
+    })))
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let workspaces = database::workspace::find_by_user_id(user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "workspaces": workspaces
+    })))
+}
+
+pub async fn rename(
+    State(state): State<AppState>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+    Json(payload): Json<RenameWorkspaceReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let member = managed(&workspace)?;
+
+    let workspace =
+        database::workspace::rename(member.workspace_id.clone(), payload.name, &state.db)
+            .await
+            .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "workspace": workspace
+    })))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+) -> Result<impl IntoResponse, AppError> {
+    let member = workspace.require()?;
+    if member.role != WorkspaceRole::Owner {
+        return Err(AppError::Forbidden(anyhow!(
+            "Only the owner can delete workspace {}",
+            member.workspace_id
+        )));
+    }
+
+    database::workspace::delete(member.workspace_id.clone(), &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
+
+pub async fn members(
+    State(state): State<AppState>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+) -> Result<impl IntoResponse, AppError> {
+    let member = workspace.require()?;
+
+    let members = database::workspace::find_members(member.workspace_id.clone(), &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "members": members
+    })))
+}
+
+pub async fn invite(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+    Json(payload): Json<InviteMemberReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let member = managed(&workspace)?;
+
+    let invitee = database::user::find_by_email(&payload.email, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?
+        .ok_or_else(|| AppError::NotFound(anyhow!("No user registered with the given email")))?;
+    match database::workspace::find_membership(&member.workspace_id, &invitee.id, &state.db).await {
+        Ok(_) => {
+            return Err(AppError::UniqueViolation(anyhow!(
+                "User is already a member of workspace {}",
+                member.workspace_id
+            )))
+        }
+        Err(DbErr::RecordNotFound(_)) => {}
+        Err(err) => return Err(AppError::from_db_error(err)),
+    }
+
+    let invitation = database::workspace::invite(
+        member.workspace_id.clone(),
+        user_id,
+        invitee.id,
+        payload.role,
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "invitation": invitation
+    })))
+}
+
+pub async fn invitations(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let invitations = database::workspace::find_invitations(user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "invitations": invitations
+    })))
+}
+
+pub async fn accept(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<InvitationIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let member = database::workspace::respond(payload.id, user_id, true, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "member": member
+    })))
+}
+
+pub async fn decline(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Json(payload): Json<InvitationIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    database::workspace::respond(payload.id, user_id, false, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
+
+pub async fn update_member(
+    State(state): State<AppState>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+    Json(payload): Json<UpdateMemberReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let member = managed(&workspace)?;
+
+    let member = database::workspace::update_role(
+        member.workspace_id.clone(),
+        payload.user_id,
+        payload.role,
+        &state.db,
+    )
+    .await
+    .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "member": member
+    })))
+}
+
+/// Removes a member, which members can do on their own to leave the workspace
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    Extension(workspace): Extension<ActiveWorkspace>,
+    Json(payload): Json<MemberIDReq>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+    let member = if payload.user_id == user_id {
+        workspace.require()?
+    } else {
+        managed(&workspace)?
+    };
+
+    database::workspace::remove_member(member.workspace_id.clone(), payload.user_id, &state.db)
+        .await
+        .map_err(AppError::from_db_error)?;
+
+    Ok(Json(json!({
+        "status": "ok"
+    })))
+}
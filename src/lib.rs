@@ -5,5 +5,6 @@ pub mod error;
 pub mod handler;
 pub mod middleware;
 pub mod model;
+pub mod storage;
 pub mod token;
 pub mod utils;
@@ -1,4 +1,5 @@
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{delete, get, patch, post},
     Router,
@@ -7,17 +8,28 @@ use log::{error, info};
 use std::time::Duration;
 use todoapp_rs::{
     config::{state::AppState, ENV},
-    handler::{auth, todo, user},
-    middleware::auth::{auth_m, reauth_m},
+    database,
+    handler::{
+        auth, project, share, subtask, tag, todo, todo_attachment, todo_comment, user, v1,
+        workspace,
+    },
+    middleware::{
+        auth::{auth_m, reauth_m},
+        deprecation::deprecation_m,
+        idempotency::idempotency_m,
+    },
+    storage::traits::Storage,
+    utils::time,
 };
 use tokio::{net::TcpListener, signal};
-use tower::ServiceBuilder;
 use tower_http::{timeout::TimeoutLayer, trace::TraceLayer};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let state = AppState::new().await;
 
+    tokio::spawn(purge_trash(state.clone()));
+
     let app = Router::new()
         .nest(
             "/auth",
@@ -45,23 +57,156 @@ async fn main() -> anyhow::Result<()> {
                     delete(user::delete)
                         .layer(middleware::from_fn_with_state(state.clone(), reauth_m)),
                 )
+                .layer(middleware::from_fn_with_state(state.clone(), idempotency_m))
                 .layer(middleware::from_fn_with_state(state.clone(), auth_m)),
         )
         .nest(
             "/todo",
             Router::new()
-                .route("/create", post(todo::create))
-                .route("/list", get(todo::list))
-                .route("/update", patch(todo::update))
+                .route("/", get(todo::find_many))
+                .route(
+                    "/create",
+                    post(todo::create).layer(middleware::from_fn(deprecation_m)),
+                )
+                .route(
+                    "/list",
+                    get(todo::list).layer(middleware::from_fn(deprecation_m)),
+                )
+                .route("/search", get(todo::search))
+                .route(
+                    "/update",
+                    patch(todo::update).layer(middleware::from_fn(deprecation_m)),
+                )
                 .route("/mark", patch(todo::mark))
-                .route("/delete", delete(todo::delete))
+                .route("/move", patch(todo::reposition))
+                .route("/assign", patch(todo::assign))
+                .route(
+                    "/delete",
+                    delete(todo::delete).layer(middleware::from_fn(deprecation_m)),
+                )
+                .route("/bulk", post(todo::bulk))
+                .route("/archive", patch(todo::archive))
+                .route("/archive/completed", patch(todo::archive_completed))
+                .route("/trash", get(todo::trash))
+                .route("/restore", patch(todo::restore))
+                .route("/undo", patch(todo::undo))
+                .route(
+                    "/:id",
+                    get(todo::find).layer(middleware::from_fn(deprecation_m)),
+                )
+                .route("/:id/history", get(todo::history))
+                .route("/:id/comments", get(todo_comment::list))
+                .route("/:id/attachments", get(todo_attachment::list))
+                .nest(
+                    "/subtask",
+                    Router::new()
+                        .route("/add", post(subtask::add))
+                        .route("/list", get(subtask::list))
+                        .route("/toggle", patch(subtask::toggle))
+                        .route("/reorder", patch(subtask::reorder))
+                        .route("/delete", delete(subtask::delete)),
+                )
+                .nest(
+                    "/comment",
+                    Router::new()
+                        .route("/add", post(todo_comment::add))
+                        .route("/edit", patch(todo_comment::edit))
+                        .route("/delete", delete(todo_comment::delete)),
+                )
+                .nest(
+                    "/attachment",
+                    Router::new().route("/delete", delete(todo_attachment::delete)),
+                )
+                .layer(middleware::from_fn_with_state(state.clone(), idempotency_m))
+                .layer(middleware::from_fn_with_state(state.clone(), auth_m)),
+        )
+        .nest(
+            "/attachment",
+            Router::new().route("/download", get(todo_attachment::download)),
+        )
+        .nest(
+            "/v1",
+            Router::new()
+                .route("/todos", get(v1::todo::list).post(v1::todo::create))
+                .route(
+                    "/todos/:id",
+                    get(v1::todo::find)
+                        .patch(v1::todo::update)
+                        .delete(v1::todo::delete),
+                )
+                .layer(middleware::from_fn_with_state(state.clone(), idempotency_m))
                 .layer(middleware::from_fn_with_state(state.clone(), auth_m)),
         )
-        .layer(
-            ServiceBuilder::new()
-                .layer(TraceLayer::new_for_http())
-                .layer(TimeoutLayer::new(Duration::from_secs(10))),
+        .nest(
+            "/project",
+            Router::new()
+                .route("/create", post(project::create))
+                .route("/list", get(project::list))
+                .route("/rename", patch(project::rename))
+                .route("/archive", patch(project::archive))
+                .route("/delete", delete(project::delete))
+                .layer(middleware::from_fn_with_state(state.clone(), idempotency_m))
+                .layer(middleware::from_fn_with_state(state.clone(), auth_m)),
+        )
+        .nest(
+            "/share",
+            Router::new()
+                .route("/invite", post(share::invite))
+                .route("/list", get(share::list))
+                .route("/invitations", get(share::invitations))
+                .route("/accept", patch(share::accept))
+                .route("/decline", patch(share::decline))
+                .route("/update", patch(share::update))
+                .route("/delete", delete(share::delete))
+                .layer(middleware::from_fn_with_state(state.clone(), idempotency_m))
+                .layer(middleware::from_fn_with_state(state.clone(), auth_m)),
+        )
+        .nest(
+            "/workspace",
+            Router::new()
+                .route("/create", post(workspace::create))
+                .route("/list", get(workspace::list))
+                .route("/rename", patch(workspace::rename))
+                .route("/delete", delete(workspace::delete))
+                .route("/members", get(workspace::members))
+                .route("/invite", post(workspace::invite))
+                .route("/invitations", get(workspace::invitations))
+                .route("/accept", patch(workspace::accept))
+                .route("/decline", patch(workspace::decline))
+                .route("/member/update", patch(workspace::update_member))
+                .route("/member/remove", delete(workspace::remove_member))
+                .layer(middleware::from_fn_with_state(state.clone(), idempotency_m))
+                .layer(middleware::from_fn_with_state(state.clone(), auth_m)),
+        )
+        .nest(
+            "/tag",
+            Router::new()
+                .route("/create", post(tag::create))
+                .route("/list", get(tag::list))
+                .route("/update", patch(tag::update))
+                .route("/delete", delete(tag::delete))
+                .route("/attach", post(tag::attach))
+                .route("/detach", delete(tag::detach))
+                .layer(middleware::from_fn_with_state(state.clone(), idempotency_m))
+                .layer(middleware::from_fn_with_state(state.clone(), auth_m)),
+        )
+        .layer(TimeoutLayer::new(Duration::from_secs(10)))
+        // uploads are merged after the timeout above, as they take longer
+        .merge(
+            Router::new()
+                .route(
+                    "/todo/:id/attachments",
+                    post(todo_attachment::upload).layer(DefaultBodyLimit::max(
+                        ENV.attachment_max_size as usize + todo_attachment::MULTIPART_OVERHEAD,
+                    )),
+                )
+                .layer(middleware::from_fn_with_state(state.clone(), idempotency_m))
+                .layer(middleware::from_fn_with_state(state.clone(), auth_m))
+                .layer(TimeoutLayer::new(Duration::from_secs(
+                    ENV.attachment_upload_timeout,
+                ))),
         )
+        .layer(TraceLayer::new_for_http())
         .with_state(state.clone());
 
     info!("Listening on port {}", &ENV.port);
@@ -77,6 +222,36 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+pub async fn purge_trash(state: AppState) {
+    let mut interval = tokio::time::interval(Duration::from_secs(ENV.todo_trash_purge_interval));
+
+    loop {
+        interval.tick().await;
+
+        let deleted_before = time::now() - ENV.todo_trash_retention as i64;
+        let storage_keys = match database::todo::purge_deleted(deleted_before, &state.db).await {
+            Ok((0, _)) => continue,
+            Ok((purged, storage_keys)) => {
+                info!("Purged {} todos from the trash", purged);
+                storage_keys
+            }
+            Err(err) => {
+                error!("Failed to purge the todo trash: {}", err);
+                continue;
+            }
+        };
+
+        for storage_key in storage_keys {
+            if let Err(err) = state.storage.delete(&storage_key).await {
+                error!(
+                    "Failed to remove the attachment file {}: {}",
+                    storage_key, err
+                );
+            }
+        }
+    }
+}
+
 pub async fn shutdown(state: AppState) {
     let ctrl_c = async {
         signal::ctrl_c().await.unwrap_or_else(|_| {
@@ -1,5 +1,7 @@
 use crate::{
     config::state::AppState,
+    database,
+    entity::workspace_member,
     error::AppError,
     token::{
         claims::Claims,
@@ -16,6 +18,39 @@ use axum::{
     response::IntoResponse,
 };
 
+/// Id of the session (refresh token) the access token was issued for
+#[derive(Debug, Clone)]
+pub struct SessionId(pub String);
+
+/// Workspace picked with the `x-workspace-id` header and the membership of the
+/// user in it, `None` when acting in the personal space
+#[derive(Debug, Clone)]
+pub struct ActiveWorkspace(pub Option<workspace_member::Model>);
+
+impl ActiveWorkspace {
+    pub fn id(&self) -> Option<String> {
+        self.0.as_ref().map(|member| member.workspace_id.clone())
+    }
+
+    /// Id of the workspace new todos go to, guests can not add any
+    pub fn writable_id(&self) -> Result<Option<String>, AppError> {
+        match &self.0 {
+            Some(member) if !member.role.can_write() => Err(AppError::Forbidden(anyhow!(
+                "Guests can not write to workspace {}",
+                member.workspace_id
+            ))),
+            member => Ok(member.as_ref().map(|member| member.workspace_id.clone())),
+        }
+    }
+
+    /// Membership of the picked workspace, for the routes that need one
+    pub fn require(&self) -> Result<&workspace_member::Model, AppError> {
+        self.0
+            .as_ref()
+            .ok_or_else(|| AppError::BadRequest(anyhow!("Missing x-workspace-id header")))
+    }
+}
+
 pub async fn auth_m(
     State(state): State<AppState>,
     mut req: Request,
@@ -33,14 +68,30 @@ pub async fn auth_m(
         .ok_or_else(|| AppError::Unauthorized(anyhow!("Missing Authorization header")))?
         .to_owned();
 
-    let user_id = Access::default(state)
+    let claims = Access::default(state.clone())
         .verify(access_token, TokenType::Access)
         .await
-        .map_err(AppError::from_token_error)?
-        .sub()
-        .to_owned();
+        .map_err(AppError::from_token_error)?;
+
+    let workspace = match req.headers().get("x-workspace-id") {
+        Some(workspace_id) => {
+            let workspace_id = workspace_id
+                .to_str()
+                .map_err(|_| AppError::BadRequest(anyhow!("Invalid x-workspace-id header")))?;
+
+            Some(
+                database::workspace::find_membership(workspace_id, claims.sub(), &state.db)
+                    .await
+                    .map_err(AppError::from_db_error)?,
+            )
+        }
+        None => None,
+    };
 
-    req.extensions_mut().insert(user_id);
+    req.extensions_mut().insert(claims.sub().to_owned());
+    req.extensions_mut()
+        .insert(SessionId(claims.rjti().to_owned()));
+    req.extensions_mut().insert(ActiveWorkspace(workspace));
     Ok(next.run(req).await)
 }
 
@@ -0,0 +1,20 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+/// Flags the responses of legacy routes that have a successor in the `/v1` API
+pub async fn deprecation_m(req: Request, next: Next) -> Response {
+    let mut response = next.run(req).await;
+
+    let headers = response.headers_mut();
+    headers.insert("deprecation", HeaderValue::from_static("true"));
+    headers.insert(
+        header::LINK,
+        HeaderValue::from_static("</v1/todos>; rel=\"successor-version\""),
+    );
+
+    response
+}
@@ -0,0 +1,216 @@
+use crate::{
+    config::{state::AppState, ENV},
+    error::AppError,
+};
+use anyhow::anyhow;
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+    Extension,
+};
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Largest request or response body that gets buffered for a keyed request
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// How long a key stays claimed by a request that never finishes, it only
+/// has to outlive the request timeout, which is the longest for uploads
+fn pending_ttl() -> u64 {
+    ENV.attachment_upload_timeout.max(60)
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdempotencyRecord {
+    fingerprint: String,
+    response: Option<StoredResponse>,
+}
+
+fn get_key(user_id: &str, key: &str) -> String {
+    format!("idempotency_key:{}:{}", user_id, key)
+}
+
+/// Replays the first response of a mutating request carrying an
+/// `Idempotency-Key` header to retries of that request within the TTL
+pub async fn idempotency_m(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<String>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !matches!(
+        *req.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    ) {
+        return Ok(next.run(req).await);
+    }
+
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(req).await);
+    };
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|key| (1..=255).contains(&key.len()))
+        .ok_or_else(|| AppError::BadRequest(anyhow!("Idempotency-Key header is not valid")))?
+        .to_owned();
+
+    let (parts, body) = req.into_parts();
+
+    let mut hasher = Sha256::new();
+    hasher.update(parts.method.as_str());
+    hasher.update(parts.uri.to_string());
+    // Uploads can be far larger than what is worth buffering, so they are
+    // streamed through and fingerprinted by their method and path alone
+    let body = if is_multipart(&parts.headers) {
+        body
+    } else {
+        let body = to_bytes(body, MAX_BODY_SIZE)
+            .await
+            .map_err(|err| AppError::BadRequest(anyhow!(err)))?;
+        hasher.update(&body);
+        Body::from(body)
+    };
+    let fingerprint = format!("{:x}", hasher.finalize());
+
+    let redis_key = get_key(&user_id, &key);
+    let mut conn = state.get_redis_conn::<AppError>().await?;
+
+    let claimed: Option<String> = redis::cmd("SET")
+        .arg(&redis_key)
+        .arg(to_json(&IdempotencyRecord {
+            fingerprint: fingerprint.clone(),
+            response: None,
+        })?)
+        .arg("NX")
+        .arg("EX")
+        .arg(pending_ttl())
+        .query_async(&mut conn)
+        .await?;
+
+    if claimed.is_none() {
+        let record: Option<String> = redis::cmd("GET")
+            .arg(&redis_key)
+            .query_async(&mut conn)
+            .await?;
+        let record: IdempotencyRecord = record
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|err| AppError::Other(err.into()))?
+            .ok_or_else(|| {
+                AppError::IdempotencyKeyInUse(anyhow!("Idempotency key {} just expired", key))
+            })?;
+
+        if record.fingerprint != fingerprint {
+            return Err(AppError::IdempotencyKeyReused(anyhow!(
+                "Idempotency key {} was used for a different request",
+                key
+            )));
+        }
+
+        return match record.response {
+            Some(response) => replay(response),
+            None => Err(AppError::IdempotencyKeyInUse(anyhow!(
+                "Request with idempotency key {} is still in progress",
+                key
+            ))),
+        };
+    }
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_BODY_SIZE).await {
+        Ok(body) => body,
+        Err(err) => {
+            redis::cmd("DEL")
+                .arg(&redis_key)
+                .query_async::<()>(&mut conn)
+                .await?;
+            return Err(AppError::Other(anyhow!(err)));
+        }
+    };
+
+    // server errors are not cached so the client can retry them
+    if parts.status.is_server_error() {
+        redis::cmd("DEL")
+            .arg(&redis_key)
+            .query_async::<()>(&mut conn)
+            .await?;
+    } else {
+        let stored = StoredResponse {
+            status: parts.status.as_u16(),
+            headers: parts
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_owned()))
+                })
+                .collect(),
+            body: BASE64_STANDARD.encode(&body),
+        };
+
+        redis::cmd("SET")
+            .arg(&redis_key)
+            .arg(to_json(&IdempotencyRecord {
+                fingerprint,
+                response: Some(stored),
+            })?)
+            .arg("EX")
+            .arg(ENV.idempotency_key_ttl)
+            .query_async::<()>(&mut conn)
+            .await?;
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+fn is_multipart(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| {
+            content_type
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("multipart/")
+        })
+}
+
+fn to_json(record: &IdempotencyRecord) -> Result<String, AppError> {
+    serde_json::to_string(record).map_err(|err| AppError::Other(err.into()))
+}
+
+fn replay(stored: StoredResponse) -> Result<Response, AppError> {
+    let body = BASE64_STANDARD
+        .decode(stored.body)
+        .map_err(|err| AppError::Other(err.into()))?;
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() =
+        StatusCode::from_u16(stored.status).map_err(|err| AppError::Other(err.into()))?;
+
+    let headers = response.headers_mut();
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+
+    Ok(response)
+}
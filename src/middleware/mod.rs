@@ -1 +1,3 @@
 pub mod auth;
+pub mod deprecation;
+pub mod idempotency;
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct CreateProjectReq {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct RenameProjectReq {
+    #[validate(length(equal = 26, message = "provide a valid project id"))]
+    pub id: String,
+
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct ArchiveProjectReq {
+    #[validate(length(equal = 26, message = "provide a valid project id"))]
+    pub id: String,
+
+    #[serde(default = "default_archived")]
+    pub archived: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteProjectMode {
+    Cascade,
+    #[default]
+    Inbox,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct DeleteProjectReq {
+    #[validate(length(equal = 26, message = "provide a valid project id"))]
+    pub id: String,
+
+    #[serde(default)]
+    pub mode: DeleteProjectMode,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ProjectListQuery {
+    pub include_archived: Option<bool>,
+}
+
+fn default_archived() -> bool {
+    true
+}
@@ -0,0 +1,196 @@
+use crate::utils::time::SECONDS_IN_DAY;
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use validator::ValidationError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Recurrence {
+    Daily {
+        #[serde(default = "default_interval")]
+        interval: u32,
+    },
+    Weekly {
+        weekdays: Vec<Weekday>,
+    },
+    Monthly {
+        day: u32,
+    },
+    AfterCompletion {
+        days: u32,
+    },
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+impl Recurrence {
+    /// Returns the due date of the occurrence that follows a todo due at
+    /// `due_at` and completed at `completed_at`. Calendar based rules are
+    /// moved past `completed_at` so that completing an overdue todo does not
+    /// create another overdue one.
+    pub fn next_due(&self, due_at: Option<i64>, completed_at: i64) -> Option<i64> {
+        if let Recurrence::AfterCompletion { days } = self {
+            return Some(completed_at + i64::from(*days) * SECONDS_IN_DAY);
+        }
+
+        let mut next = DateTime::<Utc>::from_timestamp(due_at.unwrap_or(completed_at), 0)?;
+        loop {
+            next = self.step(next)?;
+            if next.timestamp() > completed_at {
+                return Some(next.timestamp());
+            }
+        }
+    }
+
+    fn step(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Recurrence::Daily { interval } => {
+                from.checked_add_days(Days::new(u64::from(*interval)))
+            }
+            Recurrence::Weekly { weekdays } => (1..=7)
+                .filter_map(|offset| from.checked_add_days(Days::new(offset)))
+                .find(|date| weekdays.contains(&date.weekday())),
+            Recurrence::Monthly { day } => {
+                let month = from
+                    .date_naive()
+                    .with_day(1)?
+                    .checked_add_months(Months::new(1))?;
+                let date = month.with_day((*day).min(days_in_month(month)?))?;
+
+                Some(date.and_time(from.time()).and_utc())
+            }
+            Recurrence::AfterCompletion { .. } => None,
+        }
+    }
+}
+
+fn days_in_month(first_of_month: NaiveDate) -> Option<u32> {
+    first_of_month
+        .checked_add_months(Months::new(1))?
+        .pred_opt()
+        .map(|date| date.day())
+}
+
+pub fn validate_recurrence(recurrence: &Recurrence) -> Result<(), ValidationError> {
+    let message = match recurrence {
+        Recurrence::Daily { interval } if *interval == 0 => "interval must be at least 1 day",
+        Recurrence::Weekly { weekdays } if weekdays.is_empty() => {
+            "weekly recurrence needs at least one weekday"
+        }
+        Recurrence::Monthly { day } if !(1..=31).contains(day) => {
+            "day of the month must be between 1 and 31"
+        }
+        Recurrence::AfterCompletion { days } if *days == 0 => "days must be at least 1",
+        _ => return Ok(()),
+    };
+
+    Err(ValidationError::new("recurrence").with_message(Cow::Owned(message.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Recurrence;
+    use chrono::{TimeZone, Utc, Weekday};
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> i64 {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .timestamp()
+    }
+
+    #[test]
+    fn clamps_monthly_days_to_the_end_of_the_month() {
+        let monthly = Recurrence::Monthly { day: 31 };
+
+        assert_eq!(
+            monthly.next_due(Some(at(2025, 1, 31, 9)), at(2025, 1, 31, 10)),
+            Some(at(2025, 2, 28, 9))
+        );
+        assert_eq!(
+            monthly.next_due(Some(at(2024, 1, 31, 9)), at(2024, 1, 31, 10)),
+            Some(at(2024, 2, 29, 9))
+        );
+        assert_eq!(
+            monthly.next_due(Some(at(2025, 2, 28, 9)), at(2025, 2, 28, 10)),
+            Some(at(2025, 3, 31, 9))
+        );
+        assert_eq!(
+            monthly.next_due(Some(at(2025, 12, 31, 9)), at(2025, 12, 31, 10)),
+            Some(at(2026, 1, 31, 9))
+        );
+    }
+
+    #[test]
+    fn rolls_weekly_recurrence_over_to_the_next_week() {
+        let mondays = Recurrence::Weekly {
+            weekdays: vec![Weekday::Mon],
+        };
+        let mondays_and_fridays = Recurrence::Weekly {
+            weekdays: vec![Weekday::Mon, Weekday::Fri],
+        };
+
+        // 2025-01-03 is a Friday
+        assert_eq!(
+            mondays.next_due(Some(at(2025, 1, 3, 9)), at(2025, 1, 3, 10)),
+            Some(at(2025, 1, 6, 9))
+        );
+        assert_eq!(
+            mondays.next_due(Some(at(2025, 1, 6, 9)), at(2025, 1, 6, 10)),
+            Some(at(2025, 1, 13, 9))
+        );
+        assert_eq!(
+            mondays_and_fridays.next_due(Some(at(2025, 1, 6, 9)), at(2025, 1, 6, 10)),
+            Some(at(2025, 1, 10, 9))
+        );
+        assert_eq!(
+            mondays_and_fridays.next_due(Some(at(2025, 12, 26, 9)), at(2025, 12, 26, 10)),
+            Some(at(2025, 12, 29, 9))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_completion_time_without_a_due_date() {
+        let completed_at = at(2025, 1, 31, 18);
+
+        assert_eq!(
+            Recurrence::Daily { interval: 2 }.next_due(None, completed_at),
+            Some(at(2025, 2, 2, 18))
+        );
+        assert_eq!(
+            Recurrence::Monthly { day: 31 }.next_due(None, completed_at),
+            Some(at(2025, 2, 28, 18))
+        );
+        assert_eq!(
+            Recurrence::Weekly {
+                weekdays: vec![Weekday::Fri]
+            }
+            .next_due(None, completed_at),
+            Some(at(2025, 2, 7, 18))
+        );
+    }
+
+    #[test]
+    fn moves_overdue_occurrences_past_the_completion() {
+        assert_eq!(
+            Recurrence::Daily { interval: 1 }
+                .next_due(Some(at(2025, 1, 1, 9)), at(2025, 1, 10, 12)),
+            Some(at(2025, 1, 11, 9))
+        );
+        assert_eq!(
+            Recurrence::Monthly { day: 15 }.next_due(Some(at(2025, 1, 15, 9)), at(2025, 3, 20, 12)),
+            Some(at(2025, 4, 15, 9))
+        );
+    }
+
+    #[test]
+    fn counts_after_completion_from_the_completion_time() {
+        assert_eq!(
+            Recurrence::AfterCompletion { days: 3 }
+                .next_due(Some(at(2025, 1, 1, 9)), at(2025, 1, 10, 12)),
+            Some(at(2025, 1, 13, 12))
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use validator::{Validate, ValidationError};
+
+use crate::entity::{sea_orm_active_enums::SharePermission, share};
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+#[validate(schema(function = "validate_create_share"))]
+pub struct CreateShareReq {
+    #[validate(email(message = "please provide a valid email address"))]
+    pub email: String,
+
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub todo_id: Option<String>,
+
+    #[validate(length(equal = 26, message = "provide a valid project id"))]
+    pub project_id: Option<String>,
+
+    #[serde(default = "default_permission")]
+    pub permission: SharePermission,
+}
+
+fn validate_create_share(data: &CreateShareReq) -> Result<(), ValidationError> {
+    if data.todo_id.is_some() == data.project_id.is_some() {
+        return Err(ValidationError::new("target")
+            .with_message(Cow::Borrowed("provide either todo_id or project_id")));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct UpdateShareReq {
+    #[validate(length(equal = 26, message = "provide a valid share id"))]
+    pub id: String,
+
+    pub permission: SharePermission,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct ShareIDReq {
+    #[validate(length(equal = 26, message = "provide a valid share id"))]
+    pub id: String,
+}
+
+/// A pending share as seen by the invited user
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShareInvitation {
+    #[serde(flatten)]
+    pub share: share::Model,
+    pub owner_name: String,
+
+    /// Title of the shared todo or name of the shared project
+    pub title: String,
+}
+
+/// Marks a todo that another user shared with the current one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedBy {
+    pub owner_id: String,
+    pub owner_name: String,
+    pub permission: SharePermission,
+}
+
+fn default_permission() -> SharePermission {
+    SharePermission::Viewer
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct AddSubtaskReq {
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub todo_id: String,
+
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "title must be between 1 and 255 characters"
+    ))]
+    pub title: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct SubtaskIDReq {
+    #[validate(length(equal = 26, message = "provide a valid subtask id"))]
+    pub id: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct ReorderSubtaskReq {
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub todo_id: String,
+
+    #[validate(length(min = 1, message = "provide the subtask ids in their new order"))]
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct SubtaskListQuery {
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub todo_id: String,
+}
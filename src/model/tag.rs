@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use validator::{Validate, ValidationError};
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct CreateTagReq {
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "name must be between 1 and 50 characters"
+    ))]
+    pub name: String,
+
+    #[validate(custom(function = "validate_color"))]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+#[validate(schema(function = "validate_update_tag"))]
+pub struct UpdateTagReq {
+    #[validate(length(equal = 26, message = "provide a valid tag id"))]
+    pub id: String,
+
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "name must be between 1 and 50 characters"
+    ))]
+    pub name: Option<String>,
+
+    #[validate(custom(function = "validate_color"))]
+    pub color: Option<String>,
+}
+
+/// An update without any field would not reach the database, and sea-orm
+/// would return the tag without checking who owns it
+fn validate_update_tag(data: &UpdateTagReq) -> Result<(), ValidationError> {
+    if data.name.is_none() && data.color.is_none() {
+        return Err(ValidationError::new("fields")
+            .with_message(Cow::Borrowed("provide at least one of name or color")));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct TagIDReq {
+    #[validate(length(equal = 26, message = "provide a valid tag id"))]
+    pub id: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct TodoTagReq {
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub todo_id: String,
+
+    #[validate(length(equal = 26, message = "provide a valid tag id"))]
+    pub tag_id: String,
+}
+
+fn validate_color(color: &str) -> Result<(), ValidationError> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color.chars().skip(1).all(|c| c.is_ascii_hexdigit());
+
+    if !valid {
+        return Err(
+            ValidationError::new("color").with_message(Cow::Owned(String::from(
+                "color must be a hex value such as #1e90ff",
+            ))),
+        );
+    }
+
+    Ok(())
+}
@@ -1,17 +1,126 @@
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use std::borrow::Cow;
+use validator::{Validate, ValidationError, ValidationErrors};
 
-use crate::entity::todo;
+use super::{
+    recurrence::{validate_recurrence, Recurrence},
+    share::SharedBy,
+};
+use crate::{
+    entity::{sea_orm_active_enums::Priority, todo, user},
+    utils::{avatar, utils::deserialize_some},
+};
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize)]
+pub struct SubtaskCount {
+    pub done: i64,
+    pub total: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TodoItem {
+    #[serde(flatten)]
+    pub todo: todo::Model,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtasks: Option<SubtaskCount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared_by: Option<SharedBy>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<Assignee>,
+
+    pub comment_count: i64,
+}
+
+impl From<todo::Model> for TodoItem {
+    fn from(todo: todo::Model) -> Self {
+        Self {
+            todo,
+            subtasks: None,
+            shared_by: None,
+            assignee: None,
+            comment_count: 0,
+        }
+    }
+}
+
+/// The user a todo is delegated to
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Assignee {
+    pub id: String,
+    pub name: String,
+    pub photo_url: String,
+}
+
+impl From<user::Model> for Assignee {
+    fn from(user: user::Model) -> Self {
+        Self {
+            photo_url: avatar::photo_url(&user.name),
+            id: user.id,
+            name: user.name,
+        }
+    }
+}
+
+/// A todo before and after a write
+#[derive(Debug, Clone)]
+pub struct TodoChange {
+    pub before: todo::Model,
+    pub after: todo::Model,
+
+    /// Next occurrence created when a recurring todo got completed
+    pub occurrence: Option<todo::Model>,
+}
 
 #[derive(Default, Deserialize, Serialize)]
 pub struct PaginatedTodo {
-    pub todos: Vec<todo::Model>,
+    pub todos: Vec<TodoItem>,
     pub next_offset: Option<u64>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
     pub has_next: bool,
 }
 
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct TodoHighlight {
+    pub title: String,
+    pub content: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TodoSearchHit {
+    #[serde(flatten)]
+    pub todo: todo::Model,
+    pub rank: f32,
+    pub highlight: TodoHighlight,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct TodoSearchResult {
+    pub results: Vec<TodoSearchHit>,
+    pub next_offset: Option<u64>,
+    pub has_next: bool,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct TodoSearchQuery {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "search query must be between 1 and 255 characters"
+    ))]
+    pub q: String,
+
+    pub include_archived: Option<bool>,
+}
+
 #[derive(Debug, Validate, Serialize, Deserialize)]
+#[validate(schema(function = "validate_update_todo"))]
 pub struct UpdateTodoReq {
+    /// Taken from the path on the `/v1` routes
+    #[serde(default)]
     #[validate(length(equal = 26, message = "provide a valid todo id"))]
     pub id: String,
 
@@ -30,6 +139,36 @@ pub struct UpdateTodoReq {
     pub content: Option<String>,
 
     pub completed: Option<bool>,
+
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[validate(range(min = 0, message = "due date must be a valid epoch timestamp"))]
+    pub due_at: Option<Option<i64>>,
+
+    pub priority: Option<Priority>,
+
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[validate(length(equal = 26, message = "provide a valid project id"))]
+    pub project_id: Option<Option<String>>,
+
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[validate(custom(function = "validate_recurrence"))]
+    pub recurrence: Option<Option<Recurrence>>,
+}
+
+fn validate_update_todo(data: &UpdateTodoReq) -> Result<(), ValidationError> {
+    if data.title.is_none()
+        && data.content.is_none()
+        && data.completed.is_none()
+        && data.due_at.is_none()
+        && data.priority.is_none()
+        && data.project_id.is_none()
+        && data.recurrence.is_none()
+    {
+        return Err(ValidationError::new("fields")
+            .with_message(Cow::Borrowed("provide at least one field to update")));
+    }
+
+    Ok(())
 }
 
 #[derive(Debug, Validate, Serialize, Deserialize)]
@@ -47,6 +186,17 @@ pub struct CreateTodoReq {
         message = "content must be between 3 and 255 characters"
     ))]
     pub content: String,
+
+    #[validate(range(min = 0, message = "due date must be a valid epoch timestamp"))]
+    pub due_at: Option<i64>,
+
+    pub priority: Option<Priority>,
+
+    #[validate(length(equal = 26, message = "provide a valid project id"))]
+    pub project_id: Option<String>,
+
+    #[validate(custom(function = "validate_recurrence"))]
+    pub recurrence: Option<Recurrence>,
 }
 
 #[derive(Debug, Validate, Serialize, Deserialize)]
@@ -54,3 +204,267 @@ pub struct TodoIDReq {
     #[validate(length(equal = 26, message = "provide a valid todo id"))]
     pub id: String,
 }
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct TodoIDsQuery {
+    /// Comma separated todo ids
+    #[validate(custom(function = "validate_todo_ids"))]
+    pub ids: String,
+}
+
+pub const MAX_TODO_IDS: usize = 100;
+
+impl TodoIDsQuery {
+    pub fn ids(&self) -> Vec<String> {
+        self.ids
+            .split(',')
+            .map(|id| id.trim().to_string())
+            .collect()
+    }
+}
+
+fn validate_todo_ids(ids: &str) -> Result<(), ValidationError> {
+    let ids: Vec<&str> = ids.split(',').map(str::trim).collect();
+
+    let err_message = if ids.len() > MAX_TODO_IDS {
+        format!("provide at most {} todo ids", MAX_TODO_IDS)
+    } else if ids.iter().any(|id| id.len() != 26) {
+        String::from("provide valid comma separated todo ids")
+    } else {
+        return Ok(());
+    };
+
+    Err(ValidationError::new("ids").with_message(Cow::Owned(err_message)))
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct ArchiveTodoReq {
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub id: String,
+
+    #[serde(default = "default_archived")]
+    pub archived: bool,
+}
+
+fn default_archived() -> bool {
+    true
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+#[validate(schema(function = "validate_move_todo"))]
+pub struct MoveTodoReq {
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub id: String,
+
+    /// Place the todo right after this one
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub after_id: Option<String>,
+
+    /// Place the todo right before this one
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub before_id: Option<String>,
+}
+
+fn validate_move_todo(data: &MoveTodoReq) -> Result<(), ValidationError> {
+    let err_message = if data.after_id.is_none() && data.before_id.is_none() {
+        "provide after_id, before_id or both"
+    } else if data.after_id.as_ref() == Some(&data.id) || data.before_id.as_ref() == Some(&data.id)
+    {
+        "a todo can not be moved next to itself"
+    } else {
+        return Ok(());
+    };
+
+    Err(ValidationError::new("move").with_message(Cow::Borrowed(err_message)))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    Create(CreateTodoReq),
+    Update(UpdateTodoReq),
+    Mark(MarkTodoReq),
+    Delete(TodoIDReq),
+}
+
+impl Validate for BulkOperation {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            BulkOperation::Create(data) => data.validate(),
+            BulkOperation::Update(data) => data.validate(),
+            BulkOperation::Mark(data) => data.validate(),
+            BulkOperation::Delete(data) => data.validate(),
+        }
+    }
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct BulkTodoReq {
+    #[validate(length(min = 1, max = 100, message = "provide between 1 and 100 operations"))]
+    pub operations: Vec<BulkOperation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkResult {
+    pub index: usize,
+    pub status: u16,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub todo: Option<todo::Model>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct AssignTodoReq {
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub id: String,
+
+    /// Email of the assignee, `null` takes the todo back
+    #[validate(email(message = "please provide a valid email address"))]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct MarkTodoReq {
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub id: String,
+
+    /// Also complete every subtask when the todo gets completed
+    #[serde(default)]
+    pub cascade: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DueFilter {
+    Overdue,
+    Today,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoSort {
+    #[default]
+    Priority,
+    CreatedAt,
+    UpdatedAt,
+    Title,
+    Position,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Default, Validate, Serialize, Deserialize)]
+#[validate(schema(function = "validate_todo_filter"))]
+pub struct TodoFilter {
+    pub completed: Option<bool>,
+
+    pub due: Option<DueFilter>,
+
+    #[validate(range(min = 0, message = "due_before must be a valid epoch timestamp"))]
+    pub due_before: Option<i64>,
+
+    #[validate(length(equal = 26, message = "provide a valid tag id"))]
+    pub tag: Option<String>,
+
+    /// A project id, or `inbox` for todos that do not belong to any project
+    #[validate(custom(function = "validate_project_filter"))]
+    pub project: Option<String>,
+
+    /// A user id, or `me` for todos assigned to the current user
+    #[validate(custom(function = "validate_assigned_to_filter"))]
+    pub assigned_to: Option<String>,
+
+    #[validate(range(min = 0, message = "created_after must be a valid epoch timestamp"))]
+    pub created_after: Option<i64>,
+
+    #[validate(range(min = 0, message = "created_before must be a valid epoch timestamp"))]
+    pub created_before: Option<i64>,
+
+    #[validate(range(min = 0, message = "updated_after must be a valid epoch timestamp"))]
+    pub updated_after: Option<i64>,
+
+    #[validate(range(min = 0, message = "updated_before must be a valid epoch timestamp"))]
+    pub updated_before: Option<i64>,
+
+    pub sort: Option<TodoSort>,
+
+    pub order: Option<SortOrder>,
+
+    pub include_archived: Option<bool>,
+
+    /// Embed the done/total subtask counts in each listed todo
+    pub with_subtasks: Option<bool>,
+}
+
+pub const INBOX: &str = "inbox";
+
+pub const ME: &str = "me";
+
+fn validate_todo_filter(filter: &TodoFilter) -> Result<(), ValidationError> {
+    let is_empty_range = |after: Option<i64>, before: Option<i64>| {
+        after
+            .zip(before)
+            .is_some_and(|(after, before)| after >= before)
+    };
+
+    let checks = [
+        (
+            is_empty_range(filter.created_after, filter.created_before),
+            "created_after must be earlier than created_before",
+        ),
+        (
+            is_empty_range(filter.updated_after, filter.updated_before),
+            "updated_after must be earlier than updated_before",
+        ),
+        (
+            filter.due == Some(DueFilter::Overdue) && filter.completed == Some(true),
+            "completed todos are never overdue, remove either due=overdue or completed=true",
+        ),
+        (
+            filter.order.is_some() && filter.sort.is_none(),
+            "order can only be used together with sort",
+        ),
+    ];
+
+    for (not_valid, err_message) in checks {
+        if not_valid {
+            return Err(
+                ValidationError::new("filter").with_message(Cow::Owned(err_message.to_string()))
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_project_filter(project: &str) -> Result<(), ValidationError> {
+    if project != INBOX && project.len() != 26 {
+        return Err(
+            ValidationError::new("project").with_message(Cow::Owned(String::from(
+                "project must be a valid project id or inbox",
+            ))),
+        );
+    }
+
+    Ok(())
+}
+
+fn validate_assigned_to_filter(assigned_to: &str) -> Result<(), ValidationError> {
+    if assigned_to != ME && assigned_to.len() != 26 {
+        return Err(
+            ValidationError::new("assigned_to").with_message(Cow::Owned(String::from(
+                "assigned_to must be a valid user id or me",
+            ))),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use crate::entity::todo_attachment;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct AttachmentIDReq {
+    #[validate(length(equal = 26, message = "provide a valid attachment id"))]
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadQuery {
+    pub token: String,
+}
+
+/// A file uploaded to the storage that is yet to be recorded
+#[derive(Debug)]
+pub struct StoredFile {
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub storage_key: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TodoAttachmentItem {
+    #[serde(flatten)]
+    pub attachment: todo_attachment::Model,
+
+    /// Signed URL the file can be fetched from without the access token
+    /// until it expires
+    pub download_url: String,
+    pub download_url_expires_at: i64,
+}
@@ -0,0 +1,42 @@
+use crate::entity::todo_comment;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct AddCommentReq {
+    #[validate(length(equal = 26, message = "provide a valid todo id"))]
+    pub todo_id: String,
+
+    #[validate(length(
+        min = 1,
+        max = 5000,
+        message = "body must be between 1 and 5000 characters"
+    ))]
+    pub body: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct EditCommentReq {
+    #[validate(length(equal = 26, message = "provide a valid comment id"))]
+    pub id: String,
+
+    #[validate(length(
+        min = 1,
+        max = 5000,
+        message = "body must be between 1 and 5000 characters"
+    ))]
+    pub body: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct CommentIDReq {
+    #[validate(length(equal = 26, message = "provide a valid comment id"))]
+    pub id: String,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct PaginatedTodoComment {
+    pub comments: Vec<todo_comment::Model>,
+    pub next_offset: Option<u64>,
+    pub has_next: bool,
+}
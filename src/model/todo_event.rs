@@ -0,0 +1,9 @@
+use crate::entity::todo_event;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct PaginatedTodoEvent {
+    pub events: Vec<todo_event::Model>,
+    pub next_offset: Option<u64>,
+    pub has_next: bool,
+}
@@ -0,0 +1,202 @@
+use crate::entity::todo;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::todo::TodoChange;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoSnapshot {
+    /// The todo as it was before the action
+    pub todo: todo::Model,
+
+    /// Version the action left the todo at, the undo is refused once the todo
+    /// moved past it
+    pub version: i32,
+}
+
+/// Everything needed to revert one destructive action
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UndoRecord {
+    pub snapshots: Vec<UndoSnapshot>,
+
+    /// Todos created by the action, removed again on undo
+    pub created: Vec<UndoSnapshot>,
+}
+
+impl UndoRecord {
+    /// A todo changed more than once keeps its earliest snapshot, only the
+    /// version it was left at moves forward
+    pub fn push_change(&mut self, change: &TodoChange) {
+        self.merge(
+            UndoSnapshot {
+                todo: change.before.clone(),
+                version: change.after.version,
+            },
+            false,
+        );
+
+        if let Some(occurrence) = &change.occurrence {
+            self.push_created(occurrence);
+        }
+    }
+
+    pub fn push_created(&mut self, todo: &todo::Model) {
+        self.merge(
+            UndoSnapshot {
+                todo: todo.clone(),
+                version: todo.version,
+            },
+            true,
+        );
+    }
+
+    /// Adds the record of a later action, merged the same way as its changes
+    pub fn append(&mut self, other: UndoRecord) {
+        for snapshot in other.snapshots {
+            self.merge(snapshot, false);
+        }
+        for snapshot in other.created {
+            self.merge(snapshot, true);
+        }
+    }
+
+    fn merge(&mut self, snapshot: UndoSnapshot, created: bool) {
+        match self
+            .snapshots
+            .iter_mut()
+            .chain(self.created.iter_mut())
+            .find(|known| known.todo.id == snapshot.todo.id)
+        {
+            Some(known) => known.version = snapshot.version,
+            None if created => self.created.push(snapshot),
+            None => self.snapshots.push(snapshot),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty() && self.created.is_empty()
+    }
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct UndoReq {
+    #[validate(length(equal = 26, message = "provide a valid undo token"))]
+    pub undo_token: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UndoRecord;
+    use crate::{entity::todo, model::todo::TodoChange};
+
+    fn todo(id: &str, title: &str, version: i32) -> todo::Model {
+        todo::Model {
+            id: id.to_owned(),
+            user_id: String::from("U1"),
+            title: title.to_owned(),
+            content: String::from("content"),
+            completed: false,
+            created_at: 0,
+            updated_at: 0,
+            due_at: None,
+            priority: Default::default(),
+            project_id: None,
+            recurrence: None,
+            deleted_at: None,
+            archived: false,
+            position: String::from("a0"),
+            version,
+            workspace_id: None,
+            assignee_id: None,
+        }
+    }
+
+    fn change(before: todo::Model, title: &str) -> TodoChange {
+        let after = todo::Model {
+            title: title.to_owned(),
+            version: before.version + 1,
+            ..before.clone()
+        };
+
+        TodoChange {
+            before,
+            after,
+            occurrence: None,
+        }
+    }
+
+    /// Bulk operations record one action each and append it to the record
+    fn bulk(actions: Vec<UndoRecord>) -> UndoRecord {
+        let mut record = UndoRecord::default();
+        for action in actions {
+            record.append(action);
+        }
+        record
+    }
+
+    #[test]
+    fn keeps_the_first_snapshot_and_the_last_version() {
+        let update = change(todo("T1", "first", 1), "second");
+        let mark = change(update.after.clone(), "third");
+
+        let record = bulk(vec![
+            {
+                let mut action = UndoRecord::default();
+                action.push_change(&update);
+                action
+            },
+            {
+                let mut action = UndoRecord::default();
+                action.push_change(&mark);
+                action
+            },
+        ]);
+
+        assert_eq!(record.snapshots.len(), 1);
+        assert_eq!(record.snapshots[0].todo.title, "first");
+        assert_eq!(record.snapshots[0].version, 3);
+        assert!(record.created.is_empty());
+    }
+
+    #[test]
+    fn folds_changes_of_a_created_todo_into_its_creation() {
+        let created = todo("T1", "first", 1);
+        let update = change(created.clone(), "second");
+
+        let record = bulk(vec![
+            {
+                let mut action = UndoRecord::default();
+                action.push_created(&created);
+                action
+            },
+            {
+                let mut action = UndoRecord::default();
+                action.push_change(&update);
+                action
+            },
+        ]);
+
+        assert!(record.snapshots.is_empty());
+        assert_eq!(record.created.len(), 1);
+        assert_eq!(record.created[0].version, 2);
+    }
+
+    #[test]
+    fn keeps_todos_apart() {
+        let record = bulk(vec![
+            {
+                let mut action = UndoRecord::default();
+                action.push_change(&change(todo("T1", "one", 1), "changed"));
+                action
+            },
+            {
+                let mut action = UndoRecord::default();
+                action.push_change(&change(todo("T2", "two", 4), "changed"));
+                action
+            },
+        ]);
+
+        assert_eq!(record.snapshots.len(), 2);
+        assert_eq!(record.snapshots[1].version, 5);
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use validator::{Validate, ValidationError};
+
+use crate::entity::{
+    sea_orm_active_enums::WorkspaceRole, workspace, workspace_invitation, workspace_member,
+};
+
+impl WorkspaceRole {
+    /// Owners and admins manage the workspace and its members
+    pub fn can_manage(&self) -> bool {
+        matches!(self, WorkspaceRole::Owner | WorkspaceRole::Admin)
+    }
+
+    /// Guests can only read the todos of the workspace
+    pub fn can_write(&self) -> bool {
+        !matches!(self, WorkspaceRole::Guest)
+    }
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct CreateWorkspaceReq {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct RenameWorkspaceReq {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct InviteMemberReq {
+    #[validate(email(message = "please provide a valid email address"))]
+    pub email: String,
+
+    #[serde(default = "default_role")]
+    #[validate(custom(function = "validate_assignable_role"))]
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct UpdateMemberReq {
+    #[validate(length(equal = 26, message = "provide a valid user id"))]
+    pub user_id: String,
+
+    #[validate(custom(function = "validate_assignable_role"))]
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct MemberIDReq {
+    #[validate(length(equal = 26, message = "provide a valid user id"))]
+    pub user_id: String,
+}
+
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct InvitationIDReq {
+    #[validate(length(equal = 26, message = "provide a valid invitation id"))]
+    pub id: String,
+}
+
+/// A workspace along with the role of the current user in it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceItem {
+    #[serde(flatten)]
+    pub workspace: workspace::Model,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceMemberItem {
+    #[serde(flatten)]
+    pub member: workspace_member::Model,
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceInvitationItem {
+    #[serde(flatten)]
+    pub invitation: workspace_invitation::Model,
+    pub workspace_name: String,
+}
+
+/// Ownership is only given when the workspace is created
+fn validate_assignable_role(role: &WorkspaceRole) -> Result<(), ValidationError> {
+    if *role == WorkspaceRole::Owner {
+        return Err(ValidationError::new("role")
+            .with_message(Cow::Borrowed("role must be admin, member or guest")));
+    }
+
+    Ok(())
+}
+
+fn default_role() -> WorkspaceRole {
+    WorkspaceRole::Member
+}
@@ -0,0 +1,14 @@
+use anyhow::Error as AnyhowError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("object not found for the key : {0}")]
+    NotFound(String),
+
+    #[error("invalid object key : {0}")]
+    InvalidKey(String),
+
+    #[error("other error : {0}")]
+    Other(#[source] AnyhowError),
+}
@@ -0,0 +1,65 @@
+use super::{error::StorageError, traits::Storage};
+use axum::body::Bytes;
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+use tokio::fs;
+
+/// Keeps the objects as files under a directory of the local filesystem
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Resolves a key under the root, keys that could escape it are rejected
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(StorageError::InvalidKey(key.to_owned()));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, _: &str, data: Bytes) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|err| StorageError::Other(err.into()))?;
+        }
+
+        fs::write(path, data)
+            .await
+            .map_err(|err| StorageError::Other(err.into()))
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        match fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Bytes::from(data)),
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                Err(StorageError::NotFound(key.to_owned()))
+            }
+            Err(err) => Err(StorageError::Other(err.into())),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match fs::remove_file(self.path(key)?).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(StorageError::Other(err.into())),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+pub mod error;
+pub mod local;
+pub mod s3;
+pub mod traits;
+
+use crate::config::ENV;
+use anyhow::anyhow;
+use axum::body::Bytes;
+use error::StorageError;
+use local::LocalStorage;
+use s3::S3Storage;
+use traits::Storage;
+
+/// Storage picked with `ATTACHMENT_STORAGE`
+#[derive(Debug, Clone)]
+pub enum FileStorage {
+    Local(LocalStorage),
+    S3(S3Storage),
+}
+
+impl FileStorage {
+    pub fn from_env() -> Result<Self, StorageError> {
+        if &*ENV.attachment_storage != "s3" {
+            return Ok(Self::Local(LocalStorage::new(&*ENV.attachment_local_dir)));
+        }
+
+        let required = |value: &Option<String>, name: &str| {
+            value
+                .clone()
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| {
+                    StorageError::Other(anyhow!(
+                        "{} is required for the s3 attachment storage",
+                        name
+                    ))
+                })
+        };
+
+        Ok(Self::S3(S3Storage::new(
+            required(&ENV.attachment_s3_endpoint, "ATTACHMENT_S3_ENDPOINT")?,
+            required(&ENV.attachment_s3_bucket, "ATTACHMENT_S3_BUCKET")?,
+            ENV.attachment_s3_region
+                .clone()
+                .unwrap_or_else(|| String::from("us-east-1")),
+            required(&ENV.attachment_s3_access_key, "ATTACHMENT_S3_ACCESS_KEY")?,
+            required(&ENV.attachment_s3_secret_key, "ATTACHMENT_S3_SECRET_KEY")?,
+        )))
+    }
+}
+
+impl Storage for FileStorage {
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<(), StorageError> {
+        match self {
+            Self::Local(storage) => storage.put(key, content_type, data).await,
+            Self::S3(storage) => storage.put(key, content_type, data).await,
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        match self {
+            Self::Local(storage) => storage.get(key).await,
+            Self::S3(storage) => storage.get(key).await,
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match self {
+            Self::Local(storage) => storage.delete(key).await,
+            Self::S3(storage) => storage.delete(key).await,
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use super::{error::StorageError, traits::Storage};
+use anyhow::anyhow;
+use axum::body::Bytes;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{header, Client, Method, Response, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use urlencoding::encode;
+
+const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+/// Keeps the objects in a bucket of an S3 compatible service (AWS, MinIO, ...),
+/// addressed path style and signed with AWS Signature Version 4
+#[derive(Debug, Clone)]
+pub struct S3Storage {
+    client: Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3Storage {
+    pub fn new(
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key: String,
+        secret_key: String,
+    ) -> Self {
+        Self {
+            client: Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            bucket,
+            region,
+            access_key,
+            secret_key,
+        }
+    }
+
+    fn path(&self, key: &str) -> String {
+        let key = key
+            .split('/')
+            .map(|segment| encode(segment).into_owned())
+            .collect::<Vec<String>>()
+            .join("/");
+
+        format!("/{}/{}", encode(&self.bucket), key)
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        content_type: Option<&str>,
+        body: Bytes,
+    ) -> Result<Response, StorageError> {
+        let path = self.path(key);
+        let url = Url::parse(&format!("{}{}", self.endpoint, path))
+            .map_err(|err| StorageError::Other(err.into()))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_owned(),
+            (None, _) => {
+                return Err(StorageError::Other(anyhow!(
+                    "S3 endpoint {} has no host",
+                    self.endpoint
+                )))
+            }
+        };
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, path, host, payload_hash, amz_date, SIGNED_HEADERS, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let signing_key = [self.region.as_str(), "s3", "aws4_request"].iter().fold(
+            hmac_sha256(
+                format!("AWS4{}", self.secret_key).as_bytes(),
+                date.as_bytes(),
+            ),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header(
+                header::AUTHORIZATION,
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                    self.access_key, scope, SIGNED_HEADERS, signature
+                ),
+            );
+        if let Some(content_type) = content_type {
+            request = request.header(header::CONTENT_TYPE, content_type);
+        }
+
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| StorageError::Other(err.into()))?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Err(StorageError::NotFound(key.to_owned())),
+            status if status.is_success() => Ok(response),
+            status => {
+                let message = response.text().await.unwrap_or_default();
+                Err(StorageError::Other(anyhow!(
+                    "S3 responded with {}: {}",
+                    status,
+                    message
+                )))
+            }
+        }
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<(), StorageError> {
+        self.send(Method::PUT, key, Some(content_type), data)
+            .await
+            .map(|_| ())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        self.send(Method::GET, key, None, Bytes::new())
+            .await?
+            .bytes()
+            .await
+            .map_err(|err| StorageError::Other(err.into()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match self.send(Method::DELETE, key, None, Bytes::new()).await {
+            Err(StorageError::NotFound(_)) | Ok(_) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hmac_sha256, S3Storage};
+    use crate::storage::{error::StorageError, traits::Storage};
+    use axum::{
+        body::{to_bytes, Bytes},
+        extract::{Request, State},
+        http::{header, HeaderMap, Method, StatusCode},
+        response::{IntoResponse, Response},
+        Router,
+    };
+    use sha2::{Digest, Sha256};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+    use tokio::net::TcpListener;
+
+    const ACCESS_KEY: &str = "test-access";
+    const SECRET_KEY: &str = "test-secret";
+    const REGION: &str = "us-east-1";
+
+    type Objects = Arc<Mutex<HashMap<String, (String, Bytes)>>>;
+
+    /// Rebuilds the signature from the request as it arrived, the way S3
+    /// checks it
+    fn signature_matches(method: &Method, path: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .trim()
+                .to_owned()
+        };
+
+        let authorization = header("authorization");
+        let Some(fields) = authorization.strip_prefix("AWS4-HMAC-SHA256 ") else {
+            return false;
+        };
+        let fields: HashMap<&str, &str> = fields
+            .split(", ")
+            .filter_map(|field| field.split_once('='))
+            .collect();
+        let (Some(credential), Some(signed_headers), Some(signature)) = (
+            fields.get("Credential"),
+            fields.get("SignedHeaders"),
+            fields.get("Signature"),
+        ) else {
+            return false;
+        };
+        let Some((access_key, scope)) = credential.split_once('/') else {
+            return false;
+        };
+
+        let payload_hash = header("x-amz-content-sha256");
+        if access_key != ACCESS_KEY || payload_hash != hex::encode(Sha256::digest(body)) {
+            return false;
+        }
+
+        let canonical_headers: String = signed_headers
+            .split(';')
+            .map(|name| format!("{}:{}\n", name, header(name)))
+            .collect();
+        let canonical_request = format!(
+            "{}\n{}\n\n{}\n{}\n{}",
+            method, path, canonical_headers, signed_headers, payload_hash
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            header("x-amz-date"),
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let mut scope = scope.split('/');
+        let date = scope.next().unwrap_or_default();
+        let mut key = hmac_sha256(format!("AWS4{}", SECRET_KEY).as_bytes(), date.as_bytes());
+        for part in scope {
+            key = hmac_sha256(&key, part.as_bytes());
+        }
+
+        hex::encode(hmac_sha256(&key, string_to_sign.as_bytes())) == *signature
+    }
+
+    async fn object(State(objects): State<Objects>, req: Request) -> Response {
+        let (parts, body) = req.into_parts();
+        let body = to_bytes(body, usize::MAX).await.unwrap();
+        let path = parts.uri.path().to_owned();
+
+        if !signature_matches(&parts.method, &path, &parts.headers, &body) {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+
+        let mut objects = objects.lock().unwrap();
+        match parts.method {
+            Method::PUT => {
+                let content_type = parts
+                    .headers
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_owned();
+                objects.insert(path, (content_type, body));
+                StatusCode::OK.into_response()
+            }
+            Method::GET => match objects.get(&path) {
+                Some((content_type, data)) => {
+                    ([(header::CONTENT_TYPE, content_type.clone())], data.clone()).into_response()
+                }
+                None => StatusCode::NOT_FOUND.into_response(),
+            },
+            Method::DELETE => {
+                objects.remove(&path);
+                StatusCode::NO_CONTENT.into_response()
+            }
+            _ => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+        }
+    }
+
+    /// Starts an S3 stand-in on a free port and returns its endpoint
+    async fn serve(objects: Objects) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+        let app = Router::new().fallback(object).with_state(objects);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        endpoint
+    }
+
+    fn storage(endpoint: String, secret_key: &str) -> S3Storage {
+        S3Storage::new(
+            endpoint,
+            String::from("attachments"),
+            String::from(REGION),
+            String::from(ACCESS_KEY),
+            String::from(secret_key),
+        )
+    }
+
+    #[tokio::test]
+    async fn stores_reads_and_deletes_objects() {
+        let objects = Objects::default();
+        let storage = storage(serve(objects.clone()).await, SECRET_KEY);
+        let key = "01J00000000000000000000000/report 2024 (final).pdf";
+
+        storage
+            .put(key, "application/pdf", Bytes::from_static(b"%PDF-1.7"))
+            .await
+            .unwrap();
+        assert_eq!(
+            objects
+                .lock()
+                .unwrap()
+                .get("/attachments/01J00000000000000000000000/report%202024%20%28final%29.pdf")
+                .map(|(content_type, _)| content_type.as_str()),
+            Some("application/pdf")
+        );
+        assert_eq!(
+            storage.get(key).await.unwrap(),
+            Bytes::from_static(b"%PDF-1.7")
+        );
+
+        storage.delete(key).await.unwrap();
+        assert!(matches!(
+            storage.get(key).await,
+            Err(StorageError::NotFound(_))
+        ));
+        // deleting a missing object is not an error
+        storage.delete(key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_rejected_signatures() {
+        let storage = storage(serve(Objects::default()).await, "wrong-secret");
+
+        assert!(matches!(
+            storage
+                .put("key", "text/plain", Bytes::from_static(b"data"))
+                .await,
+            Err(StorageError::Other(_))
+        ));
+    }
+}
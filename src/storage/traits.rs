@@ -0,0 +1,21 @@
+use super::error::StorageError;
+use axum::body::Bytes;
+use std::future::Future;
+
+/// Object storage the attachment files are kept in, addressed by key
+pub trait Storage
+where
+    Self: Send + Sync,
+{
+    fn put(
+        &self,
+        key: &str,
+        content_type: &str,
+        data: Bytes,
+    ) -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    fn get(&self, key: &str) -> impl Future<Output = Result<Bytes, StorageError>> + Send;
+
+    /// Removes an object, a missing one is not an error
+    fn delete(&self, key: &str) -> impl Future<Output = Result<(), StorageError>> + Send;
+}
@@ -13,6 +13,7 @@ pub enum TokenType {
     Refresh,
     Session,
     ReAuth,
+    Attachment,
 }
 
 impl Display for TokenType {
@@ -22,6 +23,7 @@ impl Display for TokenType {
             TokenType::Refresh => "refresh_token",
             TokenType::Session => "session_token",
             TokenType::ReAuth => "reauth_token",
+            TokenType::Attachment => "attachment_token",
         };
 
         write!(f, "{token}")
@@ -7,6 +7,7 @@ use super::{
     },
 };
 use crate::entity::user::Model;
+use crate::utils::avatar;
 use crate::{config::state::AppState, error::AppError};
 use serde::{Deserialize, Serialize};
 
@@ -83,10 +84,7 @@ pub async fn factory(state: AppState, user: &Model) -> Result<TokenFactory, AppE
             user.id.clone(),
             user.email.clone(),
             user.name.clone(),
-            format!(
-                "https://api.dicebear.com/9.x/notionists/svg?seed={}",
-                &user.name
-            ),
+            avatar::photo_url(&user.name),
         ),
         TokenParams::default(),
     )
@@ -106,6 +106,9 @@ where
                 TokenType::ReAuth => {
                     unimplemented!("please implement the re-auth token verification logic")
                 }
+                TokenType::Attachment => {
+                    unimplemented!("please implement the attachment token verification logic")
+                }
             }
 
             Ok(claims)
@@ -98,7 +98,7 @@ impl Token<PrimaryClaims> for Access {
                 .arg("EX")
                 .arg(self.exp())
                 .ignore()
-                .query_async(&mut conn)
+                .query_async::<()>(&mut conn)
                 .await
                 .map_err(|err| TokenError::Other(err.into()))?;
         }
@@ -142,7 +142,7 @@ impl Access {
             .arg(claims.jti())
             .arg("KEEPTTL")
             .ignore()
-            .query_async(&mut conn)
+            .query_async::<()>(&mut conn)
             .await
             .map_err(|err| TokenError::Other(err.into()))?;
 
@@ -0,0 +1,88 @@
+use super::{params::TokenParams, response::TokenResponse};
+use crate::{
+    config::{state::AppState, ENV},
+    token::{claims::PrimaryClaims, error::TokenError, traits::Token},
+};
+use jsonwebtoken::{Algorithm, Validation};
+
+/// Short lived token signing the download URL of an attachment, its subject
+/// is the attachment id
+pub struct Attachment {
+    pub state: AppState,
+    pub attachment_id: Option<String>,
+}
+
+impl Attachment {
+    pub fn default(state: AppState) -> Self {
+        Self {
+            state,
+            attachment_id: None,
+        }
+    }
+
+    pub fn new(state: AppState, attachment_id: String) -> Self {
+        Self {
+            state,
+            attachment_id: Some(attachment_id),
+        }
+    }
+
+    fn attachment_id(&self) -> &str {
+        self.attachment_id
+            .as_deref()
+            .expect("attachment_id is required to create a new attachment token")
+    }
+
+    fn claims(&self) -> PrimaryClaims {
+        PrimaryClaims::new(self.attachment_id().to_owned(), self.exp(), None, None)
+    }
+}
+
+impl Token<PrimaryClaims> for Attachment {
+    fn state(&self) -> AppState {
+        self.state.clone()
+    }
+
+    fn public_key(&self) -> &[u8] {
+        &ENV.attachment_token_public_key
+    }
+
+    fn private_key(&self) -> &[u8] {
+        &ENV.attachment_token_private_key
+    }
+
+    fn exp(&self) -> usize {
+        ENV.attachment_token_expiration
+    }
+
+    async fn create(&self, _: TokenParams) -> Result<TokenResponse, TokenError> {
+        Ok(TokenResponse::Attachment(self.generate(&self.claims())?))
+    }
+
+    /// Like the default, but without the leeway on the expiry which would
+    /// outlast the token itself
+    fn decode(&self, token: String) -> Result<PrimaryClaims, TokenError> {
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.leeway = 0;
+
+        let claims = jsonwebtoken::decode::<PrimaryClaims>(
+            &token,
+            &self.decode_rsa_key_pem(self.public_key()),
+            &validation,
+        )
+        .map_err(|err| TokenError::Validation(err.into()))?
+        .claims;
+
+        Ok(claims)
+    }
+
+    /// The signature and expiry are all there is to check, the token is not
+    /// kept in redis
+    async fn verify(
+        &self,
+        token: String,
+        _: crate::token::TokenType,
+    ) -> Result<PrimaryClaims, TokenError> {
+        self.decode(token)
+    }
+}
@@ -1,4 +1,5 @@
 pub mod access;
+pub mod attachment;
 pub mod params;
 pub mod reauth;
 pub mod refresh;
@@ -79,7 +79,7 @@ impl Token<PrimaryClaims> for Refresh {
             .arg("EX")
             .arg(ENV.access_token_expiration)
             .ignore()
-            .query_async(&mut conn)
+            .query_async::<()>(&mut conn)
             .await
             .map_err(|err| TokenError::Other(err.into()))?;
 
@@ -118,7 +118,7 @@ impl Refresh {
             .cmd("DEL")
             .arg(TokenType::Access.get_key(&value))
             .ignore()
-            .query_async(&mut conn)
+            .query_async::<()>(&mut conn)
             .await
             .map_err(|err| TokenError::Other(err.into()))?;
 
@@ -10,6 +10,7 @@ pub enum TokenResponse {
     },
     Session(String),
     Reauth(String),
+    Attachment(String),
 }
 
 impl Display for TokenResponse {
@@ -23,6 +24,7 @@ impl Display for TokenResponse {
             } => write!(f, "{token}"),
             TokenResponse::Session(token) => write!(f, "{token}"),
             TokenResponse::Reauth(token) => write!(f, "{token}"),
+            TokenResponse::Attachment(token) => write!(f, "{token}"),
         }
     }
 }
@@ -0,0 +1,9 @@
+use urlencoding::encode;
+
+/// Avatar of a user, generated from their name
+pub fn photo_url(name: &str) -> String {
+    format!(
+        "https://api.dicebear.com/9.x/notionists/svg?seed={}",
+        encode(name)
+    )
+}
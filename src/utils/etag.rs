@@ -0,0 +1,32 @@
+use crate::error::AppError;
+use anyhow::anyhow;
+use axum::http::{header, HeaderMap, HeaderValue};
+
+/// Strong entity tag for a todo version
+pub fn etag(version: i32) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", version)).unwrap()
+}
+
+/// Reads the version expected by the `If-Match` header, `None` when the
+/// header is missing or matches any version (`*`)
+pub fn if_match(headers: &HeaderMap) -> Result<Option<i32>, AppError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+
+    let value = value
+        .to_str()
+        .map_err(|_| AppError::BadRequest(anyhow!("If-Match header is not valid")))?
+        .trim();
+
+    if value == "*" {
+        return Ok(None);
+    }
+
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .and_then(|version| version.parse().ok())
+        .map(Some)
+        .ok_or_else(|| AppError::BadRequest(anyhow!("If-Match header is not a valid todo etag")))
+}
@@ -1,3 +1,8 @@
+pub mod avatar;
+pub mod etag;
 pub mod paginate;
+pub mod position;
+pub mod time;
+#[allow(clippy::module_inception)]
 pub mod utils;
 pub mod verify;
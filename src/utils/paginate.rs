@@ -1,10 +1,54 @@
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
 pub struct Paginator {
     pub skip: u64,
     pub take: u64,
+    pub cursor: Option<Cursor>,
 }
 
 impl Default for Paginator {
     fn default() -> Self {
-        Self { skip: 0, take: 10 }
+        Self {
+            skip: 0,
+            take: 10,
+            cursor: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    After,
+    Before,
+}
+
+/// An opaque position in a listing, made of the values the row it was taken
+/// at has in the sort columns. The last one is the (unique) id, so rows with
+/// the same values are still told apart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    #[serde(rename = "d")]
+    pub direction: Direction,
+
+    /// Name of the ordering the cursor belongs to, cursors do not carry over
+    /// to another one
+    #[serde(rename = "s")]
+    pub sort: String,
+
+    #[serde(rename = "k")]
+    pub key: Vec<Value>,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        BASE64_URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let raw = BASE64_URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        serde_json::from_slice(&raw).ok()
     }
 }
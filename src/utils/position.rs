@@ -0,0 +1,263 @@
+//! Lexicographic fractional indexing, so that moving one todo only has to
+//! rewrite the position of that todo.
+//!
+//! A key is a variable length base 62 integer followed by a fraction. The
+//! first character of the integer encodes its length (`a`-`z` for positive,
+//! `A`-`Z` for negative integers) so keys compare correctly byte by byte,
+//! which is why the column uses the "C" collation. Appending and prepending
+//! only bump the integer part, and the fraction only grows when inserting
+//! between two neighbouring keys.
+
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const INTEGER_ZERO: &[u8] = b"a0";
+const SMALLEST_INTEGER: &[u8] = b"A00000000000000000000000000";
+
+/// Returns a key that sorts strictly between `lower` and `upper`, where a
+/// missing bound stands for the start or the end of the list. Returns `None`
+/// when a bound is not a valid key or the bounds are not in ascending order.
+pub fn key_between(lower: Option<&str>, upper: Option<&str>) -> Option<String> {
+    let lower = lower.map(str::as_bytes);
+    let upper = upper.map(str::as_bytes);
+
+    if lower.is_some_and(|key| !is_valid(key)) || upper.is_some_and(|key| !is_valid(key)) {
+        return None;
+    }
+
+    let key = match (lower, upper) {
+        (None, None) => INTEGER_ZERO.to_vec(),
+        (None, Some(upper)) => {
+            let (integer, fraction) = upper.split_at(integer_length(upper[0])?);
+            if integer == SMALLEST_INTEGER {
+                [integer, &midpoint(b"", Some(fraction))?].concat()
+            } else if integer < upper {
+                integer.to_vec()
+            } else {
+                decrement_integer(integer)?
+            }
+        }
+        (Some(lower), None) => {
+            let (integer, fraction) = lower.split_at(integer_length(lower[0])?);
+            match increment_integer(integer) {
+                Some(next) => next,
+                None => [integer, &midpoint(fraction, None)?].concat(),
+            }
+        }
+        (Some(lower), Some(upper)) => {
+            if lower >= upper {
+                return None;
+            }
+
+            let (lower_integer, lower_fraction) = lower.split_at(integer_length(lower[0])?);
+            let (upper_integer, upper_fraction) = upper.split_at(integer_length(upper[0])?);
+            if lower_integer == upper_integer {
+                [
+                    lower_integer,
+                    &midpoint(lower_fraction, Some(upper_fraction))?,
+                ]
+                .concat()
+            } else {
+                match increment_integer(lower_integer) {
+                    Some(next) if next.as_slice() < upper => next,
+                    _ => [lower_integer, &midpoint(lower_fraction, None)?].concat(),
+                }
+            }
+        }
+    };
+
+    String::from_utf8(key).ok()
+}
+
+fn digit(c: u8) -> Option<usize> {
+    DIGITS.iter().position(|d| *d == c)
+}
+
+fn integer_length(head: u8) -> Option<usize> {
+    match head {
+        b'a'..=b'z' => Some(usize::from(head - b'a') + 2),
+        b'A'..=b'Z' => Some(usize::from(b'Z' - head) + 2),
+        _ => None,
+    }
+}
+
+fn is_valid(key: &[u8]) -> bool {
+    let Some(length) = key.first().and_then(|head| integer_length(*head)) else {
+        return false;
+    };
+
+    key.len() >= length
+        && key != SMALLEST_INTEGER
+        && key[1..].iter().all(|c| DIGITS.contains(c))
+        && (key.len() == length || key.last() != Some(&DIGITS[0]))
+}
+
+fn increment_integer(integer: &[u8]) -> Option<Vec<u8>> {
+    let (head, mut digits) = (integer[0], integer[1..].to_vec());
+
+    for position in (0..digits.len()).rev() {
+        let next = digit(digits[position])? + 1;
+        if next < DIGITS.len() {
+            digits[position] = DIGITS[next];
+            return Some([&[head], digits.as_slice()].concat());
+        }
+        digits[position] = DIGITS[0];
+    }
+
+    match head {
+        b'Z' => Some(INTEGER_ZERO.to_vec()),
+        b'z' => None,
+        _ => {
+            let head = head + 1;
+            if head > b'a' {
+                digits.push(DIGITS[0]);
+            } else {
+                digits.pop();
+            }
+            Some([&[head], digits.as_slice()].concat())
+        }
+    }
+}
+
+fn decrement_integer(integer: &[u8]) -> Option<Vec<u8>> {
+    let (head, mut digits) = (integer[0], integer[1..].to_vec());
+    let last = DIGITS[DIGITS.len() - 1];
+
+    for position in (0..digits.len()).rev() {
+        let current = digit(digits[position])?;
+        if current > 0 {
+            digits[position] = DIGITS[current - 1];
+            return Some([&[head], digits.as_slice()].concat());
+        }
+        digits[position] = last;
+    }
+
+    match head {
+        b'a' => Some(vec![b'Z', last]),
+        b'A' => None,
+        _ => {
+            let head = head - 1;
+            if head < b'Z' {
+                digits.push(last);
+            } else {
+                digits.pop();
+            }
+            Some([&[head], digits.as_slice()].concat())
+        }
+    }
+}
+
+fn midpoint(lower: &[u8], upper: Option<&[u8]>) -> Option<Vec<u8>> {
+    if let Some(upper) = upper {
+        let prefix = upper
+            .iter()
+            .enumerate()
+            .take_while(|(i, c)| lower.get(*i).unwrap_or(&DIGITS[0]) == *c)
+            .count();
+
+        if prefix > 0 {
+            return Some(
+                [
+                    &upper[..prefix],
+                    midpoint(
+                        lower.get(prefix..).unwrap_or_default(),
+                        Some(&upper[prefix..]),
+                    )?
+                    .as_slice(),
+                ]
+                .concat(),
+            );
+        }
+    }
+
+    let lower_digit = lower.first().map_or(Some(0), |c| digit(*c))?;
+    let upper_digit = match upper {
+        Some(upper) => digit(*upper.first()?)?,
+        None => DIGITS.len(),
+    };
+
+    if upper_digit > lower_digit + 1 {
+        return Some(vec![DIGITS[(lower_digit + upper_digit) / 2]]);
+    }
+
+    match upper {
+        Some(upper) if upper.len() > 1 => Some(vec![upper[0]]),
+        _ => Some(
+            [
+                &[DIGITS[lower_digit]],
+                midpoint(lower.get(1..).unwrap_or_default(), None)?.as_slice(),
+            ]
+            .concat(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::key_between;
+
+    #[test]
+    fn starts_at_integer_zero() {
+        assert_eq!(key_between(None, None).as_deref(), Some("a0"));
+    }
+
+    #[test]
+    fn appends_and_prepends_by_integer() {
+        assert_eq!(key_between(Some("a0"), None).as_deref(), Some("a1"));
+        assert_eq!(key_between(Some("az"), None).as_deref(), Some("b00"));
+        assert_eq!(key_between(Some("Zz"), None).as_deref(), Some("a0"));
+        assert_eq!(key_between(None, Some("a0")).as_deref(), Some("Zz"));
+        assert_eq!(key_between(None, Some("b00")).as_deref(), Some("az"));
+        assert_eq!(key_between(None, Some("a0V")).as_deref(), Some("a0"));
+    }
+
+    #[test]
+    fn grows_the_fraction_between_neighbours() {
+        assert_eq!(key_between(Some("a0"), Some("a1")).as_deref(), Some("a0V"));
+        assert_eq!(key_between(Some("a0V"), Some("a1")).as_deref(), Some("a0k"));
+        assert_eq!(key_between(Some("a0"), Some("a0V")).as_deref(), Some("a0F"));
+        assert_eq!(key_between(Some("a0"), Some("a2")).as_deref(), Some("a1"));
+    }
+
+    #[test]
+    fn rejects_invalid_or_unordered_bounds() {
+        assert_eq!(key_between(Some("a1"), Some("a0")), None);
+        assert_eq!(key_between(Some("a0"), Some("a0")), None);
+        assert_eq!(key_between(Some(""), None), None);
+        assert_eq!(key_between(Some("a"), None), None);
+        assert_eq!(key_between(Some("a00"), None), None);
+        assert_eq!(key_between(None, Some("a0!")), None);
+        assert_eq!(key_between(None, Some("A00000000000000000000000000")), None);
+    }
+
+    #[test]
+    fn keeps_keys_ordered_through_many_inserts() {
+        let mut keys = vec![key_between(None, None).unwrap()];
+
+        for i in 0..500 {
+            let key = match i % 4 {
+                0 => key_between(None, Some(&keys[0])),
+                1 => key_between(Some(&keys[keys.len() - 1]), None),
+                _ => {
+                    let at = (i * 7) % (keys.len() - 1).max(1);
+                    key_between(Some(&keys[at]), keys.get(at + 1).map(String::as_str))
+                }
+            }
+            .unwrap();
+
+            keys.push(key);
+            keys.sort();
+        }
+
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn keeps_splitting_the_same_gap() {
+        let (lower, mut upper) = (String::from("a0"), String::from("a1"));
+
+        for _ in 0..100 {
+            let key = key_between(Some(&lower), Some(&upper)).unwrap();
+            assert!(lower < key && key < upper);
+            upper = key;
+        }
+    }
+}
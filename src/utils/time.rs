@@ -0,0 +1,14 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SECONDS_IN_DAY: i64 = 86_400;
+
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+pub fn start_of_day(epoch: i64) -> i64 {
+    epoch - epoch.rem_euclid(SECONDS_IN_DAY)
+}
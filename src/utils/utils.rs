@@ -21,3 +21,11 @@ where
     let s: String = String::deserialize(deserializer)?;
     Ok(s.into())
 }
+
+pub fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
@@ -62,3 +62,15 @@ pub fn redis_url(url: &str) -> Result<(), ValidationError> {
 
     Ok(())
 }
+
+pub fn attachment_storage(storage: &str) -> Result<(), ValidationError> {
+    if !["local", "s3"].contains(&storage) {
+        return Err(
+            ValidationError::new("attachment_storage").with_message(Cow::Owned(String::from(
+                "Please provide a valid attachment storage: local, s3",
+            ))),
+        );
+    }
+
+    Ok(())
+}